@@ -1,4 +1,4 @@
-use utils::{Direction, Solution, run_solution};
+use utils::{Point, Solution, char_grid, run_solution};
 
 struct Day04;
 
@@ -9,7 +9,7 @@ impl Solution for Day04 {
     type Output = usize;
 
     fn parse_input(&self, input: &str) -> Self::Input {
-        input.lines().map(|line| line.chars().collect()).collect()
+        char_grid(input).expect("The grid should be rectangular")
     }
 
     fn part1(&self, data: &Self::Input) -> Self::Output {
@@ -22,21 +22,10 @@ impl Solution for Day04 {
 }
 
 fn count_adjacent_rolls(grid: &Grid, row: usize, col: usize) -> usize {
-    let rows = grid.len();
-    let cols = grid[0].len();
-    let mut count = 0;
-
-    for dir in &Direction::all() {
-        let (dx, dy): (isize, isize) = (*dir).into();
-        let new_row = row.wrapping_add_signed(dy);
-        let new_col = col.wrapping_add_signed(dx);
-
-        if new_row < rows && new_col < cols && grid[new_row][new_col] == '@' {
-            count += 1;
-        }
-    }
-
-    count
+    Point::new(col, row)
+        .checked_neighbors8()
+        .filter(|p| grid.get(p.y).and_then(|line| line.get(p.x)) == Some(&'@'))
+        .count()
 }
 
 fn count_accessible_rolls(grid: &Grid) -> usize {
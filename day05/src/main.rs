@@ -1,4 +1,4 @@
-use utils::{run_solution, Solution};
+use utils::{Solution, ints, run_solution, sections};
 
 struct Day05;
 
@@ -13,29 +13,27 @@ impl Solution for Day05 {
     type Output = usize;
 
     fn parse_input(&self, input: &str) -> Self::Input {
-        let mut lines = input.lines();
-        let mut fresh_ranges = Vec::new();
-        let mut ingredient_ids = Vec::new();
-
-        // Parse fresh ranges
-        for line in lines.by_ref() {
-            if line.trim().is_empty() {
-                break;
-            }
-            let parts: Vec<&str> = line.split('-').collect();
-            if parts.len() == 2 {
-                let start = parts[0].parse().unwrap();
-                let end = parts[1].parse().unwrap();
-                fresh_ranges.push((start, end));
-            }
-        }
-
-        // Parse ingredient IDs
-        for line in lines {
-            if let Ok(id) = line.trim().parse() {
-                ingredient_ids.push(id);
-            }
-        }
+        let mut sections = sections(input);
+        let ranges = sections.next().unwrap_or_default();
+        let ids = sections.next().unwrap_or_default();
+
+        // Ranges are written "3-5", which `ints` reads as two positive numbers
+        let fresh_ranges = ranges
+            .lines()
+            .map(|line| {
+                let bounds: Vec<u64> = ints(line)
+                    .collect::<Result<_, _>>()
+                    .expect("Invalid fresh range");
+                let [start, end] = bounds[..] else {
+                    panic!("Fresh range should be two numbers: {line}");
+                };
+                (start, end)
+            })
+            .collect();
+
+        let ingredient_ids = ints(ids)
+            .collect::<Result<_, _>>()
+            .expect("Invalid ingredient ID");
 
         Database {
             fresh_ranges,
@@ -1,164 +1,374 @@
-use utils::{run_solution, Solution};
-
-struct Day06;
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use utils::{BigInt, ColumnGroup, ColumnTable, ParseError, Solution, run_solution};
+
+/// Worksheet solver, evaluating the problems in the integer type `T`.
+///
+/// Answers that overflow `T` are computed again with `BigInt`s, so `T` only picks the fast path:
+/// `i64` covers the puzzle input, `u128` leaves room for much larger products.
+struct Day06<T> {
+    operand: PhantomData<T>,
+}
 
-#[derive(Debug)]
-struct Problem {
-    numbers_part1: Vec<i64>,
-    numbers_part2: Vec<i64>,
-    operation: char,
+impl<T> Day06<T> {
+    fn new() -> Self {
+        Self {
+            operand: PhantomData,
+        }
+    }
 }
 
-impl Solution for Day06 {
-    type Input = Vec<Problem>;
-    type Output = i64;
+/// An operation written under a problem, applied left to right to its numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Power,
+    Min,
+    Max,
+    /// Writes the digits of the right number after the left one: `12 | 34 = 1234`
+    Concat,
+}
 
-    fn parse_input(&self, input: &str) -> Self::Input {
-        let lines: Vec<&str> = input.lines().collect();
-        if lines.is_empty() {
-            return Vec::new();
+impl TryFrom<char> for Operator {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '+' => Ok(Operator::Add),
+            '-' => Ok(Operator::Subtract),
+            '*' => Ok(Operator::Multiply),
+            '/' => Ok(Operator::Divide),
+            '%' => Ok(Operator::Remainder),
+            '^' => Ok(Operator::Power),
+            '<' => Ok(Operator::Min),
+            '>' => Ok(Operator::Max),
+            '|' => Ok(Operator::Concat),
+            _ => Err(c),
         }
+    }
+}
 
-        // Find max line length
-        let max_len = lines.iter().map(|l| l.len()).max().unwrap_or(0);
-
-        // Identify separator columns (all spaces in all lines)
-        let mut separator_cols = Vec::new();
-        for col_idx in 0..max_len {
-            let mut is_separator = true;
-            for line in &lines {
-                if let Some(ch) = line.chars().nth(col_idx)
-                    && ch != ' '
-                {
-                    is_separator = false;
-                    break;
-                }
-            }
-            if is_separator {
-                separator_cols.push(col_idx);
-            }
+impl Operator {
+    /// Applies the operator, or returns an error instead of overflowing or dividing by zero.
+    fn apply<T: Operand>(self, lhs: T, rhs: T) -> Result<T, EvalError> {
+        if matches!(self, Operator::Divide | Operator::Remainder) && rhs == T::ZERO {
+            return Err(EvalError::DivisionByZero);
         }
 
-        // Identify problem column ranges
-        let mut problem_ranges = Vec::new();
-        let mut start = 0;
+        let result = match self {
+            Operator::Add => lhs.checked_add(rhs),
+            Operator::Subtract => lhs.checked_sub(rhs),
+            Operator::Multiply => lhs.checked_mul(rhs),
+            Operator::Divide => lhs.checked_div(rhs),
+            Operator::Remainder => lhs.checked_rem(rhs),
+            Operator::Power => lhs.checked_pow(rhs),
+            Operator::Min => Some(lhs.min(rhs)),
+            Operator::Max => Some(lhs.max(rhs)),
+            Operator::Concat => lhs.checked_concat(rhs),
+        };
+
+        result.ok_or(EvalError::Overflow(self))
+    }
+}
 
-        for &sep_col in &separator_cols {
-            if sep_col > start {
-                problem_ranges.push((start, sep_col - 1));
-            }
-            start = sep_col + 1;
-        }
+/// Why a problem has no answer in the chosen integer type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EvalError {
+    Overflow(Operator),
+    DivisionByZero,
+}
 
-        // Add the last range
-        if start < max_len {
-            problem_ranges.push((start, max_len - 1));
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvalError::Overflow(operator) => write!(f, "{operator:?} overflows"),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
         }
+    }
+}
 
-        // For each problem range, extract numbers and operation
-        let mut problems = Vec::new();
+/// Integer types the worksheet can be evaluated in, with overflow-checked operations.
+trait Operand: Clone + Ord + FromStr + fmt::Display + Into<BigInt> {
+    const ZERO: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+    fn checked_pow(self, exp: Self) -> Option<Self>;
+    fn checked_concat(self, rhs: Self) -> Option<Self>;
+}
 
-        for (col_start, col_end) in problem_ranges {
-            let mut numbers_part1 = Vec::new();
-            let mut numbers_part2 = Vec::new();
-            let mut operation = ' ';
+macro_rules! impl_operand {
+    ($($t:ty),*) => {
+        $(
+            impl Operand for $t {
+                const ZERO: Self = 0;
 
-            // Part 1: Extract substring for each line, each line is a number
-            for (line_idx, line) in lines.iter().enumerate() {
-                let start_idx = col_start.min(line.len());
-                let end_idx = (col_end + 1).min(line.len());
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
 
-                if start_idx >= end_idx {
-                    continue;
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
                 }
 
-                let substring = &line[start_idx..end_idx];
-                let trimmed = substring.trim();
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
 
-                if trimmed.is_empty() {
-                    continue;
+                fn checked_div(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_div(self, rhs)
                 }
 
-                // Last line contains the operation
-                if line_idx == lines.len() - 1 {
-                    operation = trimmed.chars().next().unwrap_or(' ');
-                } else if let Ok(num) = trimmed.parse::<i64>() {
-                    numbers_part1.push(num);
+                fn checked_rem(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_rem(self, rhs)
                 }
-            }
 
-            // Part 2: Each column forms a number by reading bottom-to-top
-            // (bottom = least significant digit)
-            for col_idx in col_start..=col_end {
-                let mut digits = Vec::new();
-
-                // Read from bottom to top (excluding last line which has operator)
-                for line_idx in (0..lines.len() - 1).rev() {
-                    if let Some(ch) = lines[line_idx].chars().nth(col_idx)
-                        && ch.is_ascii_digit()
-                    {
-                        digits.push(ch);
-                    }
+                fn checked_pow(self, exp: Self) -> Option<Self> {
+                    <$t>::checked_pow(self, u32::try_from(exp).ok()?)
                 }
 
-                if !digits.is_empty() {
-                    // Digits are already in bottom-to-top order (least to most significant)
-                    // Reverse to get most-to-least significant for parsing
-                    digits.reverse();
-                    let num_str: String = digits.iter().collect();
-                    if let Ok(num) = num_str.parse::<i64>() {
-                        numbers_part2.push(num);
+                fn checked_concat(self, rhs: Self) -> Option<Self> {
+                    // Numbers on the worksheet are never negative, so `rhs` only has digits
+                    let digits = u32::try_from(rhs.to_string().len()).ok()?;
+                    let shifted = self.checked_mul((10 as $t).checked_pow(digits)?)?;
+
+                    if self < 0 as $t {
+                        shifted.checked_sub(rhs)
+                    } else {
+                        shifted.checked_add(rhs)
                     }
                 }
             }
+        )*
+    };
+}
 
-            if !numbers_part1.is_empty() && (operation == '+' || operation == '*') {
-                problems.push(Problem {
-                    numbers_part1,
-                    numbers_part2,
-                    operation,
-                });
-            }
+impl_operand!(i64, u128);
+
+/// Largest power `BigInt` computes, in bits: far beyond any worksheet answer, but still quick.
+const MAX_POWER_BITS: u64 = 1 << 20;
+
+/// The fallback when the other types overflow. Only powers bigger than `MAX_POWER_BITS` count
+/// as overflowing.
+impl Operand for BigInt {
+    const ZERO: Self = BigInt::ZERO;
+
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        Some(self - rhs)
+    }
+
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+
+    fn checked_div(self, rhs: Self) -> Option<Self> {
+        self.div_rem(&rhs).map(|(quotient, _)| quotient)
+    }
+
+    fn checked_rem(self, rhs: Self) -> Option<Self> {
+        self.div_rem(&rhs).map(|(_, remainder)| remainder)
+    }
+
+    fn checked_pow(self, exp: Self) -> Option<Self> {
+        let exp = u32::try_from(exp.to_i128()?).ok()?;
+
+        // A base of `bits` bits raised to `exp` has at least `(bits - 1) * exp + 1` of them,
+        // while 0 and ±1 stay small whatever the exponent
+        let bits = self.magnitude().bits();
+        if bits.saturating_sub(1) * u64::from(exp) >= MAX_POWER_BITS {
+            return None;
         }
 
-        problems
+        Some(self.pow(exp))
     }
 
-    fn part1(&self, data: &Self::Input) -> Self::Output {
-        data.iter()
-            .map(|problem| {
-                let mut result = problem.numbers_part1[0];
-                for &num in &problem.numbers_part1[1..] {
-                    match problem.operation {
-                        '+' => result += num,
-                        '*' => result *= num,
-                        _ => {}
-                    }
-                }
-                result
+    fn checked_concat(self, rhs: Self) -> Option<Self> {
+        let digits = u32::try_from(rhs.to_string().len()).ok()?;
+        let shifted = self.clone() * BigInt::from(10).pow(digits);
+
+        Some(if self.is_negative() {
+            shifted - rhs
+        } else {
+            shifted + rhs
+        })
+    }
+}
+
+/// Why a problem of the worksheet can't be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum WorksheetError {
+    UnknownOperator(char),
+    InvalidNumber(ParseError),
+}
+
+impl fmt::Display for WorksheetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorksheetError::UnknownOperator(c) => write!(f, "unknown operator {c:?}"),
+            WorksheetError::InvalidNumber(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for WorksheetError {}
+
+/// Which way the numbers of a problem are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Part {
+    /// One number per line
+    One,
+    /// One number per column
+    Two,
+}
+
+#[derive(Debug)]
+struct Problem<T> {
+    numbers_part1: Vec<T>,
+    numbers_part2: Vec<T>,
+    operator: Operator,
+}
+
+impl<T: Operand> Problem<T> {
+    /// Reads the problem written in a group of columns, the operator being on `operator_row`
+    /// and the numbers above it.
+    ///
+    /// Returns `None` for a group without an operator or without numbers, which isn't a problem.
+    fn parse(group: &ColumnGroup, operator_row: usize) -> Result<Option<Self>, WorksheetError> {
+        let Some(symbol) = group.cell(operator_row).chars().next() else {
+            return Ok(None);
+        };
+        let operator = Operator::try_from(symbol).map_err(WorksheetError::UnknownOperator)?;
+
+        // Part 1: each line of the problem is a number
+        let numbers_part1 = group
+            .row_numbers(0..operator_row)
+            .map_err(WorksheetError::InvalidNumber)?;
+
+        // Part 2: each column is a number, read top to bottom, columns right to left
+        let numbers_part2 = group
+            .column_numbers(0..operator_row)
+            .map_err(WorksheetError::InvalidNumber)?;
+
+        if numbers_part1.is_empty() || numbers_part2.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Problem {
+            numbers_part1,
+            numbers_part2,
+            operator,
+        }))
+    }
+
+    fn numbers(&self, part: Part) -> &[T] {
+        match part {
+            Part::One => &self.numbers_part1,
+            Part::Two => &self.numbers_part2,
+        }
+    }
+
+    /// The same problem with its numbers as `BigInt`s.
+    fn widen(&self) -> Problem<BigInt> {
+        let widen = |numbers: &[T]| numbers.iter().cloned().map(Into::into).collect();
+
+        Problem {
+            numbers_part1: widen(&self.numbers_part1),
+            numbers_part2: widen(&self.numbers_part2),
+            operator: self.operator,
+        }
+    }
+}
+
+/// Folds the numbers of a problem with its operator, from left to right.
+fn evaluate<T: Operand>(numbers: &[T], operator: Operator) -> Result<T, EvalError> {
+    let (first, rest) = numbers
+        .split_first()
+        .expect("Problems have at least one number");
+
+    rest.iter().try_fold(first.clone(), |result, num| {
+        operator.apply(result, num.clone())
+    })
+}
+
+/// Adds up the answers to every problem, stopping at the first error.
+fn grand_total<T: Operand>(problems: &[Problem<T>], part: Part) -> Result<T, EvalError> {
+    problems.iter().try_fold(T::ZERO, |total, problem| {
+        let answer = evaluate(problem.numbers(part), problem.operator)?;
+
+        total
+            .checked_add(answer)
+            .ok_or(EvalError::Overflow(Operator::Add))
+    })
+}
+
+/// Adds up the answers in `T`, starting over with `BigInt`s if anything overflows.
+///
+/// # Panics
+/// Panics if a problem divides by zero, or raises to a power over `MAX_POWER_BITS` bits.
+fn exact_grand_total<T: Operand>(problems: &[Problem<T>], part: Part) -> BigInt {
+    let total = grand_total(problems, part)
+        .map(Into::into)
+        .or_else(|err| match err {
+            EvalError::Overflow(_) => {
+                let wide: Vec<Problem<BigInt>> = problems.iter().map(Problem::widen).collect();
+                grand_total(&wide, part)
+            }
+            EvalError::DivisionByZero => Err(err),
+        });
+
+    total.unwrap_or_else(|err| panic!("The worksheet has no answer: {err}"))
+}
+
+impl<T: Operand> Solution for Day06<T> {
+    type Input = Vec<Problem<T>>;
+    type Output = BigInt;
+
+    fn parse_input(&self, input: &str) -> Self::Input {
+        let table = ColumnTable::new(input);
+
+        // The last line holds the operations, the numbers are above it
+        let Some(operator_row) = table.height().checked_sub(1) else {
+            return Vec::new();
+        };
+
+        table
+            .groups()
+            .iter()
+            .filter_map(|group| {
+                Problem::parse(group, operator_row)
+                    .unwrap_or_else(|err| panic!("Invalid worksheet: {err}"))
             })
-            .sum()
+            .collect()
+    }
+
+    fn part1(&self, data: &Self::Input) -> Self::Output {
+        exact_grand_total(data, Part::One)
     }
 
     fn part2(&self, data: &Self::Input) -> Self::Output {
-        data.iter()
-            .map(|problem| {
-                let mut result = problem.numbers_part2[0];
-                for &num in &problem.numbers_part2[1..] {
-                    match problem.operation {
-                        '+' => result += num,
-                        '*' => result *= num,
-                        _ => {}
-                    }
-                }
-                result
-            })
-            .sum()
+        exact_grand_total(data, Part::Two)
     }
 }
 
 fn main() {
-    run_solution!(Day06);
+    // `--u128` evaluates the worksheet with 128-bit unsigned integers before falling back to
+    // `BigInt`, instead of `i64`
+    match std::env::args().nth(1).as_deref() {
+        Some("--u128") => run_solution!(Day06::<u128>::new()),
+        _ => run_solution!(Day06::<i64>::new()),
+    }
 }
 
 #[cfg(test)]
@@ -172,19 +382,19 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let day = Day06;
+        let day = Day06::<i64>::new();
         let parsed_input = day.parse_input(TEST_INPUT);
 
         // Expected: 123*45*6 + 328+64+98 + 51*387*215 + 64+23+314
         // = 33210 + 490 + 4243455 + 401 = 4277556
         let part1 = day.part1(&parsed_input);
 
-        assert_eq!(part1, 4_277_556);
+        assert_eq!(part1, BigInt::from(4_277_556));
     }
 
     #[test]
     fn test_part2() {
-        let day = Day06;
+        let day = Day06::<i64>::new();
         let parsed_input = day.parse_input(TEST_INPUT);
 
         // Cephalopod math: reading columns bottom-to-top
@@ -195,6 +405,87 @@ mod tests {
         // Total: 1058 + 3253600 + 625 + 8544 = 3263827
         let part2 = day.part2(&parsed_input);
 
-        assert_eq!(part2, 3_263_827);
+        assert_eq!(part2, BigInt::from(3_263_827));
+    }
+
+    #[test]
+    fn test_operators() {
+        let apply = |c: char, lhs: i64, rhs: i64| Operator::try_from(c).unwrap().apply(lhs, rhs);
+
+        assert_eq!(apply('-', 3, 5), Ok(-2));
+        assert_eq!(apply('/', 7, 2), Ok(3));
+        assert_eq!(apply('%', 7, 2), Ok(1));
+        assert_eq!(apply('^', 2, 10), Ok(1024));
+        assert_eq!(apply('<', 7, 2), Ok(2));
+        assert_eq!(apply('>', 7, 2), Ok(7));
+        assert_eq!(apply('|', 12, 345), Ok(12_345));
+        assert_eq!(apply('|', -12, 3), Ok(-123));
+        assert_eq!(apply('/', 7, 0), Err(EvalError::DivisionByZero));
+        assert_eq!(Operator::try_from('?'), Err('?'));
+    }
+
+    #[test]
+    fn test_incomplete_problems() {
+        let day = Day06::<i64>::new();
+
+        // A lone operator and numbers without one are skipped
+        let problems = day.parse_input("12 5\n 3 4\n*  +  -");
+        assert_eq!(problems.len(), 2);
+        assert_eq!(day.part1(&problems), BigInt::from(12 * 3 + 5 + 4));
+        assert_eq!(day.parse_input("12 5\n 3 4\n*").len(), 1);
+
+        let table = ColumnTable::new("12\n3\n?");
+        assert_eq!(
+            Problem::<i64>::parse(&table.groups()[0], 2).map(|problem| problem.is_some()),
+            Err(WorksheetError::UnknownOperator('?'))
+        );
+    }
+
+    #[test]
+    fn test_checked_overflow() {
+        // 99999^5 is about 10^25, too much for an i64
+        let worksheet = "99999\n99999\n99999\n99999\n99999\n*    ";
+
+        let day = Day06::<i64>::new();
+        let problem = &day.parse_input(worksheet)[0];
+        assert_eq!(
+            evaluate(&problem.numbers_part1, problem.operator),
+            Err(EvalError::Overflow(Operator::Multiply))
+        );
+        assert_eq!(
+            evaluate(&[3_u128, 5], Operator::Subtract),
+            Err(EvalError::Overflow(Operator::Subtract))
+        );
+
+        // Both fall back to `BigInt` rather than wrap
+        assert_eq!(
+            day.part1(&day.parse_input(worksheet)),
+            BigInt::from(99_999_u128.pow(5))
+        );
+        let wide = Day06::<u128>::new();
+        let power = "99999\n99\n^";
+        assert_eq!(
+            wide.part1(&wide.parse_input(power)),
+            BigInt::from(99_999).pow(99)
+        );
+    }
+
+    #[test]
+    fn test_huge_power() {
+        // Too big even for `BigInt`, an overflow rather than hours of multiplying
+        assert_eq!(
+            evaluate(
+                &[BigInt::from(2), BigInt::from(4_000_000_000_u32)],
+                Operator::Power
+            ),
+            Err(EvalError::Overflow(Operator::Power))
+        );
+        assert_eq!(
+            evaluate(
+                &[BigInt::from(-1), BigInt::from(4_000_000_001_u32)],
+                Operator::Power
+            ),
+            Ok(BigInt::from(-1))
+        );
     }
 }
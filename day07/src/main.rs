@@ -1,147 +1,149 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-use utils::{run_solution, Solution};
+use std::fmt;
+
+use utils::{Beam, BeamCell, BeamGrid, BigUint, Direction, Point, Solution, run_solution};
 
 struct Day07;
 
 #[derive(Debug)]
 struct Manifold {
-    grid: Vec<Vec<char>>,
-    width: usize,
-    height: usize,
-    start: (usize, usize),
+    /// The manifold's cells, `^` being splitters that send beams on from both their sides
+    grid: BeamGrid,
+    /// The beam entering at `S`, moving down
+    start: Beam,
+    /// What the row-by-row pass found, shared by both parts
+    sweep: Sweep,
 }
 
-impl Solution for Day07 {
-    type Input = Manifold;
-    type Output = usize;
+/// More timelines than fit a `u128`, first reached in this row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TimelineOverflow {
+    row: usize,
+}
 
-    fn parse_input(&self, input: &str) -> Self::Input {
-        let grid: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
+impl fmt::Display for TimelineOverflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "more than {} timelines by row {}", u128::MAX, self.row)
+    }
+}
 
-        let height = grid.len();
-        let width = if height > 0 { grid[0].len() } else { 0 };
+impl std::error::Error for TimelineOverflow {}
 
-        // Find starting position 'S'
-        let mut start = (0, 0);
-        for (row, line) in grid.iter().enumerate() {
-            for (col, &ch) in line.iter().enumerate() {
-                if ch == 'S' {
-                    start = (row, col);
-                    break;
-                }
-            }
-        }
+/// What a single pass down the manifold finds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Sweep {
+    /// Splitters reached by at least one beam
+    splitters_hit: usize,
+    /// Timelines of a particle taking both ways at every splitter
+    timelines: Result<u128, TimelineOverflow>,
+}
 
-        Manifold {
-            grid,
-            width,
-            height,
-            start,
-        }
+/// Sweeps the manifold top to bottom from `start`, keeping the number of timelines with a beam
+/// in each column of the current row.
+///
+/// Beams merge by adding up their timelines, so this takes O(width × height) whatever the
+/// number of timelines. A splitter sends its timelines on from both of its sides in the next
+/// row, and those of a side off the edge end there.
+fn sweep(grid: &BeamGrid, start: Beam) -> Sweep {
+    let width = grid.width();
+    let start = start.position;
+
+    let mut beams = vec![0_u128; width];
+    let mut next = vec![0_u128; width];
+    let mut ended = 0_u128;
+    let mut splitters_hit = 0;
+    let mut overflow = None;
+
+    if let Some(beam) = beams.get_mut(start.x) {
+        *beam = 1;
     }
 
-    fn part1(&self, data: &Self::Input) -> Self::Output {
-        let mut split_count = 0;
-        let mut queue = VecDeque::new();
-        let mut seen = HashSet::new();
-        let mut splitters_hit = HashSet::new();
-
-        // Start with a beam at S, moving downward
-        // Beam format: (row, col) - the current position of the beam
-        queue.push_back(data.start);
-
-        while let Some((row, col)) = queue.pop_front() {
-            // Skip if we've already processed a beam from this position
-            if !seen.insert((row, col)) {
+    for row in start.y + 1..grid.height() {
+        for (col, &count) in beams.iter().enumerate().filter(|&(_, &count)| count > 0) {
+            if grid.get(Point::new(col, row)) != Some(BeamCell::SideSplitter) {
+                add_timelines(&mut next[col], count, row, &mut overflow);
                 continue;
             }
 
-            // Move downward from current position
-            let mut current_row = row + 1;
-
-            // Continue moving down until we hit a splitter or exit the manifold
-            while current_row < data.height {
-                let ch = data.grid[current_row][col];
-
-                if ch == '^' {
-                    // Hit a splitter - count only if this splitter hasn't been hit before
-                    if splitters_hit.insert((current_row, col)) {
-                        split_count += 1;
-                    }
-
-                    // Add new beams starting from left and right of the splitter
-                    if col > 0 {
-                        queue.push_back((current_row, col - 1));
-                    }
-                    if col + 1 < data.width {
-                        queue.push_back((current_row, col + 1));
-                    }
-
-                    break; // This beam stops here
-                }
-
-                current_row += 1;
+            splitters_hit += 1;
+            for side in [
+                col.checked_sub(1),
+                Some(col + 1).filter(|&side| side < width),
+            ] {
+                let total = match side {
+                    Some(side) => &mut next[side],
+                    None => &mut ended,
+                };
+                add_timelines(total, count, row, &mut overflow);
             }
         }
 
-        split_count
+        std::mem::swap(&mut beams, &mut next);
+        next.fill(0);
     }
 
-    fn part2(&self, data: &Self::Input) -> Self::Output {
-        // Use memoization to count timelines from each position
-        let mut memo: HashMap<(usize, usize), usize> = HashMap::new();
-        count_timelines_from(data.start.0, data.start.1, data, &mut memo)
+    // Beams still going leave through the bottom
+    for count in beams {
+        add_timelines(&mut ended, count, grid.height(), &mut overflow);
     }
-}
 
-fn count_timelines_from(
-    row: usize,
-    col: usize,
-    data: &Manifold,
-    memo: &mut HashMap<(usize, usize), usize>,
-) -> usize {
-    // Check if we've already computed this
-    if let Some(&count) = memo.get(&(row, col)) {
-        return count;
+    Sweep {
+        splitters_hit,
+        timelines: overflow.map_or(Ok(ended), Err),
     }
+}
 
-    // Move downward from current position
-    let mut current_row = row + 1;
+/// Adds `count` timelines to `total`, saturating on overflow so columns with beams stay nonzero,
+/// and keeps the row of the first overflow.
+fn add_timelines(
+    total: &mut u128,
+    count: u128,
+    row: usize,
+    overflow: &mut Option<TimelineOverflow>,
+) {
+    *total = total.checked_add(count).unwrap_or_else(|| {
+        overflow.get_or_insert(TimelineOverflow { row });
+        u128::MAX
+    });
+}
 
-    // Continue moving down until we hit a splitter or exit the manifold
-    while current_row < data.height {
-        let ch = data.grid[current_row][col];
+impl Solution for Day07 {
+    type Input = Manifold;
+    type Output = BigUint;
 
-        if ch == '^' {
-            // Hit a splitter - particle takes BOTH paths
-            let mut total = 0;
+    fn parse_input(&self, input: &str) -> Self::Input {
+        let grid = BeamGrid::parse(input).expect("The manifold should be rectangular");
 
-            // Left path
-            if col > 0 {
-                total += count_timelines_from(current_row, col - 1, data, memo);
-            } else {
-                // Can't go left (at edge), this timeline ends
-                total += 1;
-            }
+        // Find starting position 'S'
+        let start = input
+            .lines()
+            .enumerate()
+            .find_map(|(row, line)| line.chars().position(|ch| ch == 'S').map(|col| (col, row)))
+            .unwrap_or((0, 0));
 
-            // Right path
-            if col + 1 < data.width {
-                total += count_timelines_from(current_row, col + 1, data, memo);
-            } else {
-                // Can't go right (at edge), this timeline ends
-                total += 1;
-            }
+        let start = Beam::new(start.into(), Direction::Down);
+        let sweep = sweep(&grid, start);
 
-            memo.insert((row, col), total);
-            return total;
-        }
+        Manifold { grid, start, sweep }
+    }
 
-        current_row += 1;
+    fn part1(&self, data: &Self::Input) -> Self::Output {
+        // Beams in the same column merge, so each splitter counts once
+        BigUint::from(data.sweep.splitters_hit)
     }
 
-    // Exited the bottom - this is one timeline
-    memo.insert((row, col), 1);
-    1
+    fn part2(&self, data: &Self::Input) -> Self::Output {
+        // With quantum splitting the particle takes both paths at each splitter, and a side off
+        // the edge or the bottom of the manifold ends a timeline
+        match data.sweep.timelines {
+            Ok(timelines) => BigUint::from(timelines),
+            // Counted again with `BigUint`s by the propagation engine, which moves beams the
+            // same way
+            Err(_) => data
+                .grid
+                .count_timelines(data.start)
+                .expect("Beams only move down, so they can't loop"),
+        }
+    }
 }
 
 fn main() {
@@ -176,7 +178,7 @@ mod tests {
 
         let part1 = day.part1(&parsed_input);
 
-        assert_eq!(part1, 21);
+        assert_eq!(part1, BigUint::from(21_u8));
     }
 
     #[test]
@@ -188,6 +190,66 @@ mod tests {
         // Total of 40 different timelines
         let part2 = day.part2(&parsed_input);
 
-        assert_eq!(part2, 40);
+        assert_eq!(part2, BigUint::from(40_u8));
+    }
+
+    #[test]
+    fn test_part2_tall_manifold() {
+        // Splitters zig-zag between the two columns, each sending one timeline off the edge:
+        // far deeper than a recursive count could go
+        let mut input = String::from("S.\n");
+        for row in 0..50_000 {
+            input.push_str(if row % 2 == 0 { "^.\n" } else { ".^\n" });
+        }
+
+        let day = Day07;
+        assert_eq!(
+            day.part2(&day.parse_input(&input)),
+            BigUint::from(50_001_u32)
+        );
+    }
+
+    #[test]
+    fn test_sweep_overflow() {
+        // Every row is full of splitters under the beams of the row above, so the timelines
+        // nearly double with each row
+        let mut input = String::from("S.........\n");
+        for row in 0..300 {
+            let line: String = (0..10)
+                .map(|col| if col % 2 == row % 2 { '^' } else { '.' })
+                .collect();
+            input.push_str(&line);
+            input.push('\n');
+        }
+
+        let manifold = Day07.parse_input(&input);
+        // All five splitters of a row are reached from row 9 on, fewer before
+        assert_eq!(manifold.sweep.splitters_hit, 1480);
+        assert!(matches!(manifold.sweep.timelines, Err(TimelineOverflow { row }) if row < 300));
+
+        // Part 2 still has an answer
+        assert!(Day07.part2(&manifold) > BigUint::from(u128::MAX));
+    }
+
+    #[test]
+    fn test_sweep_matches_engine() {
+        // Adjacent splitters included: the second `^` of a pair doesn't act on side beams
+        let inputs = [TEST_INPUT, "..S..\n..^^.\n.^...\n.....", "S...\n^^^^\n.^.^"];
+
+        for input in inputs {
+            let manifold = Day07.parse_input(input);
+            let propagation = manifold.grid.propagate(manifold.start);
+
+            assert_eq!(
+                manifold.sweep.splitters_hit,
+                propagation.splitters_hit.len(),
+                "{input}"
+            );
+            assert_eq!(
+                manifold.sweep.timelines.map(BigUint::from).ok(),
+                manifold.grid.count_timelines(manifold.start),
+                "{input}"
+            );
+        }
     }
 }
@@ -96,15 +96,7 @@ impl Solution for Day08 {
         input
             .lines()
             .filter(|line| !line.is_empty())
-            .map(|line| {
-                let parts: Vec<i32> = line.split(',').filter_map(|s| s.parse().ok()).collect();
-
-                Point3D {
-                    x: parts[0],
-                    y: parts[1],
-                    z: parts[2],
-                }
-            })
+            .map(|line| line.parse().expect("Invalid junction box position"))
             .collect()
     }
 
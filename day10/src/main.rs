@@ -4,8 +4,7 @@
  * I used Claude to implement the `search_free_variables`, `solve_integer_linear_min` and `gaussian_elimination_gf2_min_weight` functions
  * from articles I found and my initial approach to the problem.
  */
-use regex::Regex;
-use utils::{Solution, run_solution};
+use utils::{Solution, bracketed, ints, run_solution};
 
 // Implementation by Claude Code based on my logic for exhaustive search over free variables.
 // This helper function recursively searches all combinations of free variable values
@@ -278,23 +277,23 @@ struct Machine {
 
 impl Machine {
     fn parse(line: &str) -> Self {
-        let target_re = Regex::new(r"\[([.#]+)\]").unwrap();
-        let button_re = Regex::new(r"\(([0-9,]+)\)").unwrap();
-        let joltage_re = Regex::new(r"\{([0-9,]+)\}").unwrap();
-
-        let target: Vec<bool> = target_re
-            .captures(line)
-            .map(|cap| cap[1].chars().map(|c| c == '#').collect())
+        let target: Vec<bool> = bracketed(line, '[', ']')
+            .next()
+            .transpose()
+            .expect("Invalid light diagram")
+            .map(|lights| lights.chars().map(|c| c == '#').collect())
             .unwrap_or_default();
 
-        let buttons: Vec<Vec<usize>> = button_re
-            .captures_iter(line)
-            .map(|cap| cap[1].split(',').filter_map(|s| s.parse().ok()).collect())
-            .collect();
+        let buttons: Vec<Vec<usize>> = bracketed(line, '(', ')')
+            .map(|button| ints(button?).collect())
+            .collect::<Result<_, _>>()
+            .expect("Invalid button wiring");
 
-        let joltage: Vec<i64> = joltage_re
-            .captures(line)
-            .map(|cap| cap[1].split(',').filter_map(|s| s.parse().ok()).collect())
+        let joltage: Vec<i64> = bracketed(line, '{', '}')
+            .next()
+            .map(|joltage| ints(joltage?).collect())
+            .transpose()
+            .expect("Invalid joltage requirements")
             .unwrap_or_default();
 
         Self {
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use utils::{Solution, run_solution};
+use utils::{BigUint, Memo, Solution, key_values, run_solution};
 
 type Graph<'a> = HashMap<&'a str, Vec<&'a str>>;
 
@@ -7,7 +7,7 @@ struct Day11;
 
 impl Solution for Day11 {
     type Input = String;
-    type Output = u64;
+    type Output = BigUint;
 
     fn parse_input(&self, input: &str) -> Self::Input {
         input.to_string()
@@ -15,28 +15,91 @@ impl Solution for Day11 {
 
     fn part1(&self, data: &Self::Input) -> Self::Output {
         let graph = parse_graph(data);
-        let mut memo = HashMap::new();
 
-        count_paths("you", "out", &graph, &mut memo)
+        // Count with `u64`s, and again with `BigUint`s if that overflows
+        paths_to_out::<u64>(&graph).map_or_else(
+            || paths_to_out(&graph).expect("BigUint counts don't overflow"),
+            BigUint::from,
+        )
     }
 
     fn part2(&self, data: &Self::Input) -> Self::Output {
         let graph = parse_graph(data);
-        let mut memo = HashMap::new();
 
-        // Calculate two different routes: svr -> dac -> fft -> out and svr -> fft -> dac -> out
-        let route1 = count_paths("svr", "dac", &graph, &mut memo)
-            * count_paths("dac", "fft", &graph, &mut memo)
-            * count_paths("fft", "out", &graph, &mut memo);
+        paths_through_dac_and_fft::<u64>(&graph).map_or_else(
+            || paths_through_dac_and_fft(&graph).expect("BigUint counts don't overflow"),
+            BigUint::from,
+        )
+    }
+}
+
+/// Path counts, with arithmetic that reports overflow instead of wrapping.
+trait PathCount: Clone {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn checked_add(&self, rhs: &Self) -> Option<Self>;
+    fn checked_mul(&self, rhs: &Self) -> Option<Self>;
+}
+
+impl PathCount for u64 {
+    fn zero() -> Self {
+        0
+    }
 
-        let route2 = count_paths("svr", "fft", &graph, &mut memo)
-            * count_paths("fft", "dac", &graph, &mut memo)
-            * count_paths("dac", "out", &graph, &mut memo);
+    fn one() -> Self {
+        1
+    }
 
-        route1 + route2
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        u64::checked_add(*self, *rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        u64::checked_mul(*self, *rhs)
     }
 }
 
+impl PathCount for BigUint {
+    fn zero() -> Self {
+        BigUint::ZERO
+    }
+
+    fn one() -> Self {
+        BigUint::from(1_u8)
+    }
+
+    fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+}
+
+/// Counts the paths from `you` to `out`, or `None` if the count overflows `T`.
+fn paths_to_out<T: PathCount>(graph: &Graph) -> Option<T> {
+    count_paths("you", "out", graph, &mut Memo::new())
+}
+
+/// Counts the paths from `svr` to `out` visiting both `dac` and `fft`, or `None` if the count
+/// overflows `T`.
+fn paths_through_dac_and_fft<T: PathCount>(graph: &Graph) -> Option<T> {
+    let mut memo = Memo::new();
+    let mut count = |from, to| count_paths::<T>(from, to, graph, &mut memo);
+
+    // Calculate two different routes: svr -> dac -> fft -> out and svr -> fft -> dac -> out
+    let route1 = count("svr", "dac")?
+        .checked_mul(&count("dac", "fft")?)?
+        .checked_mul(&count("fft", "out")?)?;
+
+    let route2 = count("svr", "fft")?
+        .checked_mul(&count("fft", "dac")?)?
+        .checked_mul(&count("dac", "out")?)?;
+
+    route1.checked_add(&route2)
+}
+
 fn parse_graph(input: &str) -> Graph<'_> {
     let mut graph: Graph = HashMap::new();
 
@@ -45,45 +108,36 @@ fn parse_graph(input: &str) -> Graph<'_> {
             continue;
         }
 
-        let parts: Vec<&str> = line.split(": ").collect();
-        if parts.len() == 2 {
-            let key = parts[0];
-            let neighbors: Vec<&str> = parts[1].split_whitespace().collect();
-            graph.entry(key).or_default().extend(neighbors);
-        }
+        let (key, neighbors) = key_values(line).expect("Invalid device line");
+        graph
+            .entry(key)
+            .or_default()
+            .extend(neighbors.split_whitespace());
     }
 
     graph
 }
 
-fn count_paths<'a>(
+/// Counts the paths from `curr` to `target`, or `None` if the count overflows `T`.
+fn count_paths<'a, T: PathCount>(
     curr: &'a str,
     target: &'a str,
     graph: &Graph<'a>,
-    memo: &mut HashMap<(&'a str, &'a str), u64>,
-) -> u64 {
+    memo: &mut Memo<(&'a str, &'a str), Option<T>>,
+) -> Option<T> {
     // Base case: reached target
     if curr == target {
-        return 1;
-    }
-
-    // Check memoization cache
-    let key = (curr, target);
-    if let Some(&count) = memo.get(&key) {
-        return count;
+        return Some(T::one());
     }
 
     // Count paths through all neighbors
-    let mut total = 0;
-    if let Some(neighbors) = graph.get(curr) {
-        for &next in neighbors {
-            total += count_paths(next, target, graph, memo);
+    memo.get_or_compute((curr, target), |memo| {
+        let mut total = T::zero();
+        for &next in graph.get(curr).into_iter().flatten() {
+            total = total.checked_add(&count_paths(next, target, graph, memo)?)?;
         }
-    }
-
-    // Cache result
-    memo.insert(key, total);
-    total
+        Some(total)
+    })
 }
 
 fn main() {
@@ -124,7 +178,7 @@ hhh: out";
         let day = Day11;
         let input = day.parse_input(EXAMPLE_PART1);
         let result = day.part1(&input);
-        assert_eq!(result, 5);
+        assert_eq!(result, BigUint::from(5_u8));
     }
 
     #[test]
@@ -132,6 +186,29 @@ hhh: out";
         let day = Day11;
         let input = day.parse_input(EXAMPLE_PART2);
         let result = day.part2(&input);
-        assert_eq!(result, 2);
+        assert_eq!(result, BigUint::from(2_u8));
+    }
+
+    #[test]
+    fn test_overflow_falls_back_to_biguint() {
+        // 70 diamonds in a row double the number of paths each time: 2^70 paths
+        let mut input = String::from("you: n0\n");
+        for i in 0..70 {
+            input.push_str(&format!(
+                "n{i}: a{i} b{i}\na{i}: n{}\nb{i}: n{}\n",
+                i + 1,
+                i + 1
+            ));
+        }
+        input.push_str("n70: out\n");
+
+        let graph = parse_graph(&input);
+        assert_eq!(paths_to_out::<u64>(&graph), None);
+
+        let day = Day11;
+        assert_eq!(
+            day.part1(&day.parse_input(&input)),
+            BigUint::from(1_u128 << 70)
+        );
     }
 }
@@ -1,19 +1,22 @@
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use std::collections::HashSet;
-use utils::{Solution, run_solution};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use utils::{Point, Rect, Solution, ints, key_values, run_solution, sections};
 
-/// `(row, column)`
-/// `isize` allows negative coordinates during transformations
-type Point = (isize, isize);
+/// How long a single region is searched before it is reported as undecided.
+const REGION_TIME_LIMIT: Duration = Duration::from_secs(10);
 
 /// Represents a present shape as a collection of cells.
 ///
 /// Shapes are stored as a list of relative positions from an origin point.
 /// The shape can be rotated and flipped to generate all possible orientations.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 struct Shape {
-    /// The cells that make up this shape, represented as (row, col) offsets
-    cells: Vec<Point>,
+    /// The cells that make up this shape, `x` being the column and `y` the row (pointing down).
+    /// `isize` allows negative coordinates during transformations
+    cells: Vec<Point<isize>>,
 }
 
 impl Shape {
@@ -27,7 +30,7 @@ impl Shape {
     /// ###
     /// #..
     /// ```
-    /// This creates a shape with cells at positions (0,0), (0,1), (0,2), (1,0)
+    /// This creates a shape with cells at `(x, y)` positions (0,0), (1,0), (2,0), (0,1)
     fn from_lines(lines: &[&str]) -> Self {
         let mut cells = Vec::new();
 
@@ -36,7 +39,7 @@ impl Shape {
                 if ch == '#'
                     && let (Ok(r), Ok(c)) = (isize::try_from(row), isize::try_from(col))
                 {
-                    cells.push((r, c));
+                    cells.push(Point::new(c, r));
                 }
             }
         }
@@ -46,38 +49,64 @@ impl Shape {
 
     /// Normalizes the shape by moving it so the top-left cell is at (0, 0).
     ///
-    /// This ensures shapes can be compared regardless of their absolute position.
+    /// Cells are also sorted in row-major order, so shapes can be compared
+    /// regardless of their absolute position or the order cells were produced in.
     fn normalize(&self) -> Self {
         if self.cells.is_empty() {
             return self.clone();
         }
 
-        let min_r = self.cells.iter().map(|&(r, _)| r).min().unwrap();
-        let min_c = self.cells.iter().map(|&(_, c)| c).min().unwrap();
+        let min = Point::new(
+            self.cells.iter().map(|p| p.x).min().unwrap(),
+            self.cells.iter().map(|p| p.y).min().unwrap(),
+        );
 
-        let cells = self
-            .cells
-            .iter()
-            .map(|&(r, c)| (r - min_r, c - min_c))
-            .collect();
+        let mut cells: Vec<Point<isize>> = self.cells.iter().map(|&p| p - min).collect();
+        cells.sort_unstable_by_key(|p| (p.y, p.x));
+
+        Self { cells }
+    }
+
+    /// Moves a normalized shape so its first cell in row-major order is at (0, 0).
+    ///
+    /// The packing search fills the first empty cell of the grid, which can only be
+    /// covered by this anchor cell. Other cells may end up with negative columns.
+    fn anchored(&self) -> Self {
+        let Some(&anchor) = self.cells.first() else {
+            return self.clone();
+        };
+
+        let cells = self.cells.iter().map(|&p| p - anchor).collect();
 
         Self { cells }
     }
 
+    /// Returns the `(height, width)` of the shape's bounding box.
+    fn dimensions(&self) -> (usize, usize) {
+        Rect::bounding_box(self.cells.iter().copied()).map_or((0, 0), |bounds| {
+            let size = bounds.inclusive_size();
+            (size.y.unsigned_abs(), size.x.unsigned_abs())
+        })
+    }
+
     /// Rotates the shape 90 degrees clockwise.
     ///
-    /// Uses the rotation matrix: (r, c) -> (c, -r)
+    /// Rows point down, so this is a counterclockwise rotation of the points: (x, y) -> (-y, x)
     fn rotate_90(&self) -> Self {
-        let cells = self.cells.iter().map(|&(r, c)| (c, -r)).collect();
+        let cells = self
+            .cells
+            .iter()
+            .map(Point::rotate_counterclockwise)
+            .collect();
 
         Self { cells }.normalize()
     }
 
     /// Flips the shape horizontally (mirrors across vertical axis).
     ///
-    /// Transformation: (r, c) -> (r, -c)
+    /// Transformation: (x, y) -> (-x, y)
     fn flip_horizontal(&self) -> Self {
-        let cells = self.cells.iter().map(|&(r, c)| (r, -c)).collect();
+        let cells = self.cells.iter().map(|p| Point::new(-p.x, p.y)).collect();
 
         Self { cells }.normalize()
     }
@@ -88,9 +117,10 @@ impl Shape {
     /// - 4 rotations (0°, 90°, 180°, 270°)
     /// - Flipped horizontally, then 4 more rotations
     ///
-    /// Uses a `HashSet` to automatically deduplicate symmetric shapes.
+    /// Uses a `BTreeSet` to deduplicate symmetric shapes and keep a stable order,
+    /// so orientation indices mean the same thing from one run to the next.
     fn all_transformations(&self) -> Vec<Self> {
-        let mut transformations = HashSet::new();
+        let mut transformations = BTreeSet::new();
         let mut current = self.normalize();
 
         // Generate flipped and non-flipped versions
@@ -106,81 +136,142 @@ impl Shape {
         transformations.into_iter().collect()
     }
 
+    /// Returns all unique transformations, anchored for the packing search.
+    fn orientations(&self) -> Vec<Self> {
+        self.all_transformations()
+            .iter()
+            .map(Self::anchored)
+            .collect()
+    }
+
     /// Returns the number of cells in this shape.
     fn area(&self) -> usize {
         self.cells.len()
     }
 
-    /// Checks if this shape can be placed at the given position without overlapping.
-    ///
-    /// # Arguments
-    /// * `grid` - The current grid state (true = occupied, false = empty)
-    /// * `row` - The row position for the shape's origin
-    /// * `col` - The column position for the shape's origin
-    ///
-    /// # Returns
-    /// `true` if all cells of the shape would be within bounds and unoccupied
-    fn fits_at(&self, grid: &[Vec<bool>], row: usize, col: usize) -> bool {
-        let height = grid.len();
-        let width = grid[0].len();
+    /// Returns the grid cells covered when this shape is placed at the given position.
+    fn cells_at(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.cells
+            .iter()
+            .filter_map(move |p| Some((row.checked_add_signed(p.y)?, col.checked_add_signed(p.x)?)))
+    }
+}
 
-        // Convert to isize for arithmetic with potentially negative offsets
-        let Ok(row_i) = isize::try_from(row) else {
-            return false;
-        };
-        let Ok(col_i) = isize::try_from(col) else {
-            return false;
-        };
+/// Occupancy grid storing each row as a bitmask.
+///
+/// Bit `c` of a row is set when column `c` is covered, so regions can be up to 128 cells wide.
+#[derive(Debug, Clone)]
+struct Bitboard {
+    rows: Vec<u128>,
+    width: usize,
+}
 
-        for &(dr, dc) in &self.cells {
-            let r = row_i + dr;
-            let c = col_i + dc;
+impl Bitboard {
+    /// Maximum number of columns a row mask can hold.
+    const MAX_WIDTH: usize = u128::BITS as usize;
 
-            // Check if out of bounds (negative coordinates)
-            if r < 0 || c < 0 {
-                return false;
-            }
+    /// Creates an empty grid, or `None` if `width` exceeds `Bitboard::MAX_WIDTH`.
+    fn new(width: usize, height: usize) -> Option<Self> {
+        (width <= Self::MAX_WIDTH).then(|| Self {
+            rows: vec![0; height],
+            width,
+        })
+    }
 
-            // Convert back to usize for array indexing
-            let (Ok(r), Ok(c)) = (usize::try_from(r), usize::try_from(c)) else {
-                return false;
-            };
+    /// Flips the occupancy of a single cell.
+    fn toggle(&mut self, row: usize, col: usize) {
+        self.rows[row] ^= 1 << col;
+    }
 
-            // Check if out of bounds or already occupied
-            if r >= height || c >= width || grid[r][c] {
-                return false;
+    /// Finds the first empty cell at or after the flat row-major index `start`.
+    fn first_empty(&self, start: usize) -> Option<(usize, usize)> {
+        if self.width == 0 {
+            return None;
+        }
+
+        let full = u128::MAX >> (Self::MAX_WIDTH - self.width);
+        let (first_row, first_col) = (start / self.width, start % self.width);
+
+        // Columns before `first_col` on the first row are treated as covered
+        let mut skipped = (1u128 << first_col) - 1;
+        for row in first_row..self.rows.len() {
+            let covered = self.rows[row] | skipped;
+            if covered != full {
+                return Some((row, (!covered).trailing_zeros() as usize));
             }
+            skipped = 0;
+        }
+
+        None
+    }
+}
+
+/// A shape orientation precomputed as one bitmask per row.
+///
+/// Masks are relative to the orientation's anchor: bit `c` of `rows[r]` stands for the cell
+/// `r` rows below and `c + offset` columns right of the anchor.
+#[derive(Debug, Clone)]
+struct ShapeMask {
+    rows: Vec<u128>,
+    /// Column of mask bit 0 relative to the anchor (never positive)
+    offset: isize,
+    /// Number of columns spanned by the masks
+    width: usize,
+}
+
+impl ShapeMask {
+    /// Builds the row masks of an anchored shape.
+    fn from_shape(shape: &Shape) -> Self {
+        let offset = shape.cells.iter().map(|p| p.x).min().unwrap_or(0);
+        let (height, width) = shape.dimensions();
+
+        let mut rows = vec![0; height];
+        for p in &shape.cells {
+            // Rows start at the anchor and columns at `offset`, so both are non-negative
+            rows[p.y.unsigned_abs()] |= 1 << (p.x - offset).unsigned_abs();
         }
-        true
+
+        Self {
+            rows,
+            offset,
+            width,
+        }
+    }
+
+    /// Returns the shift moving the masks onto the grid when anchored at `col`, if in bounds.
+    fn shift(&self, board: &Bitboard, row: usize, col: usize) -> Option<usize> {
+        let shift = col.checked_add_signed(self.offset)?;
+
+        (shift + self.width <= board.width && row + self.rows.len() <= board.rows.len())
+            .then_some(shift)
     }
 
-    /// Places this shape on the grid at the given position.
+    /// Checks if this orientation can be anchored at the given cell without overlapping.
     ///
-    /// # Safety
-    /// This assumes `fits_at` has been called and returned true.
-    fn place_at(&self, grid: &mut [Vec<bool>], row: usize, col: usize) {
-        for &(dr, dc) in &self.cells {
-            if let (Ok(row_i), Ok(col_i)) = (isize::try_from(row), isize::try_from(col)) {
-                let r = usize::try_from(row_i + dr).unwrap();
-                let c = usize::try_from(col_i + dc).unwrap();
-
-                grid[r][c] = true;
-            }
-        }
+    /// # Returns
+    /// `true` if all cells of the shape would be within bounds and unoccupied
+    fn fits_at(&self, board: &Bitboard, row: usize, col: usize) -> bool {
+        let Some(shift) = self.shift(board, row, col) else {
+            return false;
+        };
+
+        self.rows
+            .iter()
+            .zip(&board.rows[row..])
+            .all(|(mask, covered)| covered & (mask << shift) == 0)
     }
 
-    /// Removes this shape from the grid (used for backtracking).
+    /// Places this orientation on the grid, or removes it if it was already placed there.
     ///
-    /// # Safety
-    /// This assumes the shape was previously placed at this position.
-    fn remove_from(&self, grid: &mut [Vec<bool>], row: usize, col: usize) {
-        for &(dr, dc) in &self.cells {
-            if let (Ok(row_i), Ok(col_i)) = (isize::try_from(row), isize::try_from(col)) {
-                let r = usize::try_from(row_i + dr).unwrap();
-                let c = usize::try_from(col_i + dc).unwrap();
-
-                grid[r][c] = false;
-            }
+    /// This assumes `fits_at` has been called and returned true, or that the shape was
+    /// previously placed at this position.
+    fn toggle_at(&self, board: &mut Bitboard, row: usize, col: usize) {
+        let Some(shift) = self.shift(board, row, col) else {
+            return;
+        };
+
+        for (mask, covered) in self.rows.iter().zip(&mut board.rows[row..]) {
+            *covered ^= mask << shift;
         }
     }
 }
@@ -188,76 +279,101 @@ impl Shape {
 /// Represents a region under a Christmas tree where presents need to fit.
 #[derive(Debug)]
 struct Region {
-    width: usize,
-    height: usize,
+    /// Cells of the region, from `(0, 0)` to `(width - 1, height - 1)`, or `None` if it has none
+    bounds: Option<Rect<usize>>,
     /// Number of each shape type needed (indexed by shape ID)
     present_counts: Vec<usize>,
 }
 
+impl Region {
+    fn new(width: usize, height: usize, present_counts: Vec<usize>) -> Self {
+        Self {
+            bounds: (width > 0 && height > 0)
+                .then(|| Rect::new(Point::new(0, 0), Point::new(width - 1, height - 1))),
+            present_counts,
+        }
+    }
+
+    fn width(&self) -> usize {
+        self.bounds.map_or(0, |bounds| bounds.inclusive_size().x)
+    }
+
+    fn height(&self) -> usize {
+        self.bounds.map_or(0, |bounds| bounds.inclusive_size().y)
+    }
+
+    fn area(&self) -> usize {
+        self.bounds.map_or(0, |bounds| bounds.inclusive_area())
+    }
+}
+
 #[derive(Debug)]
 struct Input {
     shapes: Vec<Shape>,
     regions: Vec<Region>,
 }
 
+/// A count over all regions, along with the regions the time limit left out of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Tally {
+    count: usize,
+    undecided: usize,
+}
+
+impl fmt::Display for Tally {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.undecided == 0 {
+            write!(f, "{}", self.count)
+        } else {
+            // The count is only a lower bound then
+            write!(
+                f,
+                "at least {} ({} regions undecided)",
+                self.count, self.undecided
+            )
+        }
+    }
+}
+
 struct Day12;
 
 impl Solution for Day12 {
     type Input = Input;
-    type Output = usize;
+    type Output = Tally;
 
     fn parse_input(&self, input: &str) -> Self::Input {
-        let lines: Vec<&str> = input.lines().collect();
         let mut shapes = Vec::new();
         let mut regions = Vec::new();
 
-        let mut i = 0;
-        while i < lines.len() {
-            let line = lines[i].trim();
+        for section in sections(input) {
+            let mut lines = section.lines();
 
-            if line.is_empty() {
-                i += 1;
+            // Shape definitions are a "0:" line followed by their visual representation,
+            // the last section lists region requirements like "4x4: 0 0 0 0 2 0"
+            if lines
+                .next()
+                .is_some_and(|line| line.trim_end().ends_with(':'))
+            {
+                shapes.push(Shape::from_lines(&lines.collect::<Vec<_>>()));
                 continue;
             }
 
-            // Parse shape definitions (lines like "0:" followed by visual representation)
-            if line.ends_with(':') {
-                i += 1;
+            for line in section.lines() {
+                let (size, counts) = key_values(line).expect("Invalid region line");
+                let dims: Vec<usize> = ints(size)
+                    .collect::<Result<_, _>>()
+                    .expect("Invalid region size");
+                let [width, height] = dims[..] else {
+                    panic!("Region size should be two numbers: {line}");
+                };
 
-                let mut shape_lines = Vec::new();
-
-                // Collect all non-empty lines until next section
-                while i < lines.len() && !lines[i].trim().is_empty() && !lines[i].contains(':') {
-                    shape_lines.push(lines[i]);
-                    i += 1;
-                }
-
-                let shape = Shape::from_lines(&shape_lines);
-
-                shapes.push(shape);
-            }
-            // Parse region requirements (lines like "4x4: 0 0 0 0 2 0")
-            else if line.contains('x') && line.contains(':') {
-                let parts: Vec<&str> = line.split(':').collect();
-                let dims: Vec<&str> = parts[0].split('x').collect();
-                let width = dims[0].trim().parse().unwrap();
-                let height = dims[1].trim().parse().unwrap();
-
-                // Parse the count of each shape type needed
-                let present_counts: Vec<usize> = parts[1]
-                    .split_whitespace()
-                    .filter_map(|s| s.parse().ok())
-                    .collect();
-
-                regions.push(Region {
+                regions.push(Region::new(
                     width,
                     height,
-                    present_counts,
-                });
-
-                i += 1;
-            } else {
-                i += 1;
+                    ints(counts)
+                        .collect::<Result<_, _>>()
+                        .expect("Invalid present counts"),
+                ));
             }
         }
 
@@ -265,123 +381,696 @@ impl Solution for Day12 {
     }
 
     fn part1(&self, data: &Self::Input) -> Self::Output {
-        data.regions
+        let verdicts: Vec<Verdict> = data
+            .regions
             .par_iter()
-            .filter(|region| can_fit_all_presents(region, &data.shapes))
-            .count()
+            .map(|region| decide_packing(region, &data.shapes, Some(region_deadline())))
+            .collect();
+
+        Tally {
+            count: verdicts
+                .iter()
+                .filter(|verdict| matches!(verdict, Verdict::Fits(_)))
+                .count(),
+            undecided: verdicts
+                .iter()
+                .filter(|verdict| **verdict == Verdict::Undecided)
+                .count(),
+        }
     }
 
-    fn part2(&self, _data: &Self::Input) -> Self::Output {
-        0
+    /// There is no second puzzle on the last day, so part 2 reports how many presents
+    /// can actually be placed across all regions, including regions that can't fit them all.
+    fn part2(&self, data: &Self::Input) -> Self::Output {
+        let most: Vec<MostPresents> = data
+            .regions
+            .par_iter()
+            .map(|region| max_presents_that_fit(region, &data.shapes, Some(region_deadline())))
+            .collect();
+
+        Tally {
+            count: most.iter().map(|most| most.placements().len()).sum(),
+            undecided: most
+                .iter()
+                .filter(|most| matches!(most, MostPresents::AtLeast(_)))
+                .count(),
+        }
     }
 }
 
+/// Returns the time at which a region search starting now gives up.
+fn region_deadline() -> Instant {
+    Instant::now() + REGION_TIME_LIMIT
+}
+
+/// A single present placed inside a region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Placement {
+    /// Index of the shape definition this present uses
+    shape: usize,
+    /// Index into the shape's list of anchored orientations
+    orientation: usize,
+    /// Row of the cell covered by the orientation's anchor
+    row: usize,
+    /// Column of the cell covered by the orientation's anchor
+    col: usize,
+}
+
 /// Determines if all required presents can fit in the given region.
 ///
-/// Uses a backtracking algorithm to try all possible placements.
-/// First performs a quick area check - if the total area of presents
-/// exceeds the region area, it's impossible to fit them.
+/// Runs a few cheap checks before falling back to the exact search:
+/// - If the total area of presents exceeds the region area, nothing fits
+/// - If every present gets its own bounding box in a simple block layout, that layout is returned
 ///
 /// # Arguments
 /// * `region` - The region specification with dimensions and required presents
 /// * `shapes` - All available shape definitions
+/// * `deadline` - When the exact search gives up, if bounded
+fn decide_packing(region: &Region, shapes: &[Shape], deadline: Option<Instant>) -> Verdict {
+    let presents: usize = region.present_counts.iter().sum();
+    let total_area: usize = region
+        .present_counts
+        .iter()
+        .enumerate()
+        .map(|(shape_idx, &count)| count * shapes[shape_idx].area())
+        .sum();
+
+    // Quick check: if total area exceeds region, it's impossible
+    let region_area = region.area();
+    if total_area > region_area {
+        return Verdict::DoesNotFit;
+    }
+
+    // Pre-compute all anchored orientations for each shape
+    let orientations: Vec<Vec<Shape>> = shapes.iter().map(Shape::orientations).collect();
+
+    if let Some(placements) = block_layout(region, shapes, &orientations, &region.present_counts) {
+        return Verdict::Fits(placements);
+    }
+
+    Packer::new(
+        region,
+        &orientations,
+        presents,
+        region_area - total_area,
+        deadline,
+    )
+    .map_or(Verdict::Undecided, Packer::solve)
+}
+
+/// Returns the bounding box `(height, width)` large enough for any single present.
+fn block_size(shapes: &[Shape]) -> (usize, usize) {
+    shapes
+        .iter()
+        .filter(|shape| shape.area() > 0)
+        .map(Shape::dimensions)
+        .fold((1, 1), |(h, w), (sh, sw)| (h.max(sh), w.max(sw)))
+}
+
+/// Returns how many presents fit in the region when each gets its own block.
+fn block_capacity(region: &Region, shapes: &[Shape]) -> usize {
+    let (block_height, block_width) = block_size(shapes);
+
+    (region.width() / block_width) * (region.height() / block_height)
+}
+
+/// Lays presents out one per block, each block being the largest shape's bounding box.
 ///
-/// # Returns
-/// `true` if a valid arrangement exists, `false` otherwise
-fn can_fit_all_presents(region: &Region, shapes: &[Shape]) -> bool {
-    let mut presents = Vec::new();
-    let mut total_area = 0;
+/// This only succeeds when the region is roomy enough, but it decides most large regions
+/// without any search.
+///
+/// # Arguments
+/// * `counts` - Number of presents of each shape to lay out
+fn block_layout(
+    region: &Region,
+    shapes: &[Shape],
+    orientations: &[Vec<Shape>],
+    counts: &[usize],
+) -> Option<Vec<Placement>> {
+    let presents: usize = counts.iter().sum();
+    if block_capacity(region, shapes) < presents {
+        return None;
+    }
+
+    let (block_height, block_width) = block_size(shapes);
+    let blocks_per_row = region.width() / block_width;
+
+    let mut placements = Vec::with_capacity(presents);
+    for (shape_idx, &count) in counts.iter().enumerate().filter(|&(_, &count)| count > 0) {
+        // The default orientation is anchored on its first row, `anchor_col` cells from the left
+        let default = shapes[shape_idx].normalize();
+        let anchor_col = usize::try_from(default.cells.first()?.x).ok()?;
+        let anchored = default.anchored();
+        let orientation = orientations[shape_idx]
+            .iter()
+            .position(|o| *o == anchored)?;
 
-    // Build list of all presents that need to be placed
-    for (shape_idx, &count) in region.present_counts.iter().enumerate() {
         for _ in 0..count {
-            presents.push(shape_idx);
-            total_area += shapes[shape_idx].area();
+            let block = placements.len();
+            placements.push(Placement {
+                shape: shape_idx,
+                orientation,
+                row: (block / blocks_per_row) * block_height,
+                col: (block % blocks_per_row) * block_width + anchor_col,
+            });
         }
     }
 
-    // Quick check: if total area exceeds region, it's impossible
-    let region_area = region.width * region.height;
-    if total_area > region_area {
-        return false;
+    Some(placements)
+}
+
+/// The most presents of a region found to fit at once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MostPresents {
+    /// The placements of the largest packable subset
+    Exact(Vec<Placement>),
+    /// The best placements found before the exact search timed out or the region turned out
+    /// too wide for it, so more presents may fit
+    AtLeast(Vec<Placement>),
+}
+
+impl MostPresents {
+    fn placements(&self) -> &[Placement] {
+        match self {
+            Self::Exact(placements) | Self::AtLeast(placements) => placements,
+        }
+    }
+}
+
+/// Finds the largest number of the region's presents that can be placed at once.
+///
+/// The region's present counts are treated as upper limits: the search may leave any
+/// presents out. Any subset of presents that fit also fits, so the total is found by binary
+/// search between what the block layout already guarantees and the best the area allows,
+/// running only a logarithmic number of exact searches. If an exact search can't run to the
+/// end, the best placements proven so far are kept as a lower bound.
+fn max_presents_that_fit(
+    region: &Region,
+    shapes: &[Shape],
+    deadline: Option<Instant>,
+) -> MostPresents {
+    let orientations: Vec<Vec<Shape>> = shapes.iter().map(Shape::orientations).collect();
+    let region_area = region.area();
+
+    // Areas of all presents, smallest first, to bound how many can share the region
+    let mut areas: Vec<usize> = region
+        .present_counts
+        .iter()
+        .enumerate()
+        .flat_map(|(shape_idx, &count)| std::iter::repeat_n(shapes[shape_idx].area(), count))
+        .collect();
+    areas.sort_unstable();
+
+    let mut upper = 0;
+    let mut used_area = 0;
+    for &area in &areas {
+        if used_area + area > region_area {
+            break;
+        }
+        used_area += area;
+        upper += 1;
+    }
+
+    // `fits` presents are known to fit and `too_many` known not to
+    let lower = block_capacity(region, shapes).min(upper);
+    let (mut fits, mut too_many) = (lower, upper + 1);
+    let mut best = None;
+    let mut exact = true;
+    while too_many - fits > 1 {
+        let target = fits + (too_many - fits) / 2;
+        // No `target` presents can cover less than the `target` smallest ones
+        let slack = region_area - areas[..target].iter().sum::<usize>();
+
+        // Regions too wide for the exact search keep what is already proven
+        let verdict = Packer::new(region, &orientations, target, slack, deadline)
+            .map_or(Verdict::Undecided, Packer::solve);
+        match verdict {
+            Verdict::Fits(placements) => {
+                fits = target;
+                best = Some(placements);
+            }
+            Verdict::DoesNotFit => too_many = target,
+            Verdict::Undecided => {
+                exact = false;
+                break;
+            }
+        }
     }
 
-    // Initialize empty grid
-    let mut grid = vec![vec![false; region.width]; region.height];
+    let placements = best.unwrap_or_else(|| {
+        // Keep the first `lower` presents and give each of them its own block
+        let mut budget = lower;
+        let counts: Vec<usize> = region
+            .present_counts
+            .iter()
+            .map(|&count| {
+                let taken = count.min(budget);
+                budget -= taken;
+                taken
+            })
+            .collect();
 
-    // Pre-compute all transformations for each shape (optimization)
-    let transformations: Vec<Vec<Shape>> = shapes.iter().map(Shape::all_transformations).collect();
+        block_layout(region, shapes, &orientations, &counts).unwrap_or_default()
+    });
 
-    // Try to place all presents using backtracking
-    backtrack(&mut grid, &presents, &transformations, 0)
+    if exact {
+        MostPresents::Exact(placements)
+    } else {
+        MostPresents::AtLeast(placements)
+    }
 }
 
-/// Recursively attempts to place presents using backtracking.
-///
-/// This is a classic constraint satisfaction problem solver:
-/// - Try placing each present in all possible positions and orientations
-/// - If a placement works, recurse to place the next present
-/// - If no valid placement exists, backtrack (undo and try next option)
+/// Finds the side of the smallest square region that fits all of the region's presents.
 ///
-/// # Arguments
-/// * `grid` - Current state of the placement grid
-/// * `presents` - List of shape indices to place
-/// * `transformations` - Pre-computed transformations for each shape
-/// * `present_idx` - Index of the current present being placed
+/// Sides are tried in increasing order, starting from the smallest square whose area
+/// covers the presents and that is wide enough for the bulkiest one.
 ///
 /// # Returns
-/// `true` if all remaining presents can be successfully placed
-fn backtrack(
-    grid: &mut [Vec<bool>],
-    presents: &[usize],
-    transformations: &[Vec<Shape>],
-    present_idx: usize,
-) -> bool {
-    // Base case: all presents have been placed successfully
-    if present_idx == presents.len() {
-        return true;
-    }
-
-    let shape_idx = presents[present_idx];
-    let height = grid.len();
-    let width = grid[0].len();
-
-    // Try all transformations of this shape
-    for transformation in &transformations[shape_idx] {
-        // Try all possible positions
-        for row in 0..height {
-            for col in 0..width {
-                // Check if this placement is valid
-                if transformation.fits_at(grid, row, col) {
-                    // Place the shape
-                    transformation.place_at(grid, row, col);
-
-                    // Recurse to place remaining presents
-                    if backtrack(grid, presents, transformations, present_idx + 1) {
-                        return true;
-                    }
+/// The side length along with the packing found for that square, or `None` if the deadline
+/// passed first or the square grew too wide for the exact search
+fn smallest_square_region(
+    region: &Region,
+    shapes: &[Shape],
+    deadline: Option<Instant>,
+) -> Option<(usize, Vec<Placement>)> {
+    let total_area: usize = region
+        .present_counts
+        .iter()
+        .enumerate()
+        .map(|(shape_idx, &count)| count * shapes[shape_idx].area())
+        .sum();
+
+    // Every present must fit in the square, whichever way it is turned
+    let bulkiest = region
+        .present_counts
+        .iter()
+        .enumerate()
+        .filter(|&(_, &count)| count > 0)
+        .map(|(shape_idx, _)| {
+            let (height, width) = shapes[shape_idx].dimensions();
+            height.max(width)
+        })
+        .max()
+        .unwrap_or(0);
+
+    let mut side = total_area.isqrt().max(bulkiest);
+    if side * side < total_area {
+        side += 1;
+    }
+
+    loop {
+        let square = Region::new(side, side, region.present_counts.clone());
 
-                    // Backtrack: this path didn't work, remove the shape
-                    transformation.remove_from(grid, row, col);
+        match decide_packing(&square, shapes, deadline) {
+            Verdict::Fits(placements) => return Some((side, placements)),
+            Verdict::Undecided => return None,
+            Verdict::DoesNotFit => side += 1,
+        }
+    }
+}
+
+/// How often (in search nodes) a worker compares the clock against the deadline.
+const DEADLINE_CHECK_INTERVAL: usize = 1 << 12;
+
+/// Number of branches per rayon worker to aim for when splitting the top of the search tree.
+const BRANCHES_PER_THREAD: usize = 8;
+
+/// Maximum number of levels expanded up front before handing branches to rayon.
+const MAX_SPLIT_DEPTH: usize = 6;
+
+/// Exact packing search that fills the region one cell at a time.
+///
+/// At every step it looks at the first empty cell in row-major order. That cell is either
+/// covered by the anchor of some present, or deliberately left empty. Because the anchor of an
+/// orientation is its first cell in row-major order, no placement is ever tried twice, and
+/// identical presents are tracked as a count rather than as separate items so they are never
+/// permuted among themselves.
+///
+/// The top levels of the search tree are expanded up front and the resulting branches are
+/// explored in parallel. All workers stop as soon as one branch succeeds or the deadline passes.
+struct Packer {
+    /// Row masks of the anchored orientations of each shape
+    masks: Vec<Vec<ShapeMask>>,
+    /// Shape indices ordered by decreasing area, so big presents are tried first
+    order: Vec<usize>,
+    /// Initial state of the search
+    root: PackState,
+    /// Time after which the search gives up, if any
+    deadline: Option<Instant>,
+    /// Set once any branch succeeds or the deadline passes, telling all workers to stop
+    stop: AtomicBool,
+    /// Set when the search was stopped by the deadline
+    timed_out: AtomicBool,
+}
+
+/// Mutable state of one branch of the packing search.
+#[derive(Debug, Clone)]
+struct PackState {
+    /// Presents of each shape still available
+    remaining: Vec<usize>,
+    /// Total number of presents still to be placed
+    left: usize,
+    /// Number of cells that may still be left empty
+    slack: usize,
+    /// Cells covered by a present or deliberately left empty
+    board: Bitboard,
+    /// Presents placed so far
+    placements: Vec<Placement>,
+    /// Flat row-major index from which to look for the next empty cell
+    start: usize,
+    /// Number of nodes visited, used to pace deadline checks
+    nodes: usize,
+}
+
+/// Outcome of deciding whether a region's presents fit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Verdict {
+    /// All presents fit, with the placement that proves it
+    Fits(Vec<Placement>),
+    /// No arrangement exists
+    DoesNotFit,
+    /// The deadline passed before the search reached an answer, or the region is too wide
+    /// for the exact search
+    Undecided,
+}
+
+impl Packer {
+    /// Creates a packer that must place `target` of the region's presents, or `None` if the
+    /// region is wider than a `Bitboard` row.
+    ///
+    /// The region's present counts act as upper limits, and `slack` is the number of
+    /// cells the search may leave empty.
+    fn new(
+        region: &Region,
+        orientations: &[Vec<Shape>],
+        target: usize,
+        slack: usize,
+        deadline: Option<Instant>,
+    ) -> Option<Self> {
+        let mut order: Vec<usize> = (0..orientations.len()).collect();
+        order.sort_by_key(|&shape_idx| {
+            std::cmp::Reverse(orientations[shape_idx].first().map_or(0, Shape::area))
+        });
+
+        let mut remaining = region.present_counts.clone();
+        remaining.resize(orientations.len(), 0);
+
+        Some(Self {
+            masks: orientations
+                .iter()
+                .map(|shapes| shapes.iter().map(ShapeMask::from_shape).collect())
+                .collect(),
+            order,
+            root: PackState {
+                remaining,
+                left: target,
+                slack,
+                board: Bitboard::new(region.width(), region.height())?,
+                placements: Vec::new(),
+                start: 0,
+                nodes: 0,
+            },
+            deadline,
+            stop: AtomicBool::new(false),
+            timed_out: AtomicBool::new(false),
+        })
+    }
+
+    /// Runs the search, splitting the top of the tree across rayon workers.
+    fn solve(self) -> Verdict {
+        let threads = rayon::current_num_threads();
+        let mut frontier = vec![self.root.clone()];
+
+        // Expand breadth-first until there are enough branches to keep every worker busy
+        for _ in 0..MAX_SPLIT_DEPTH {
+            if frontier.len() >= threads * BRANCHES_PER_THREAD {
+                break;
+            }
+
+            let mut next = Vec::new();
+            for state in &frontier {
+                if state.left == 0 {
+                    return Verdict::Fits(state.placements.clone());
                 }
+                if self.deadline_passed() {
+                    return Verdict::Undecided;
+                }
+                next.extend(self.branches(state));
             }
+            frontier = next;
         }
+
+        let found = frontier.into_par_iter().find_map_any(|mut state| {
+            if self.search(&mut state) {
+                self.stop.store(true, Ordering::Relaxed);
+                Some(state.placements)
+            } else {
+                None
+            }
+        });
+
+        match found {
+            Some(placements) => Verdict::Fits(placements),
+            None if self.timed_out.load(Ordering::Relaxed) => Verdict::Undecided,
+            None => Verdict::DoesNotFit,
+        }
+    }
+
+    /// Returns the states reached by every possible move at the first empty cell.
+    fn branches(&self, state: &PackState) -> Vec<PackState> {
+        let Some((row, col)) = state.board.first_empty(state.start) else {
+            return Vec::new();
+        };
+        let next_start = row * state.board.width + col + 1;
+        let mut branches = Vec::new();
+
+        for &shape_idx in &self.order {
+            if state.remaining[shape_idx] == 0 {
+                continue;
+            }
+
+            for (orientation, mask) in self.masks[shape_idx].iter().enumerate() {
+                if !mask.fits_at(&state.board, row, col) {
+                    continue;
+                }
+
+                let mut branch = state.clone();
+                mask.toggle_at(&mut branch.board, row, col);
+                branch.remaining[shape_idx] -= 1;
+                branch.left -= 1;
+                branch.start = next_start;
+                branch.placements.push(Placement {
+                    shape: shape_idx,
+                    orientation,
+                    row,
+                    col,
+                });
+                branches.push(branch);
+            }
+        }
+
+        if state.slack > 0 {
+            let mut branch = state.clone();
+            branch.board.toggle(row, col);
+            branch.slack -= 1;
+            branch.start = next_start;
+            branches.push(branch);
+        }
+
+        branches
+    }
+
+    /// Checks the clock, telling every worker to stop once the deadline has passed.
+    fn deadline_passed(&self) -> bool {
+        let passed = self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline);
+        if passed {
+            self.timed_out.store(true, Ordering::Relaxed);
+            self.stop.store(true, Ordering::Relaxed);
+        }
+
+        passed
+    }
+
+    /// Tells whether this worker should give up, checking the deadline every so often.
+    fn should_stop(&self, state: &mut PackState) -> bool {
+        state.nodes += 1;
+
+        if state.nodes.is_multiple_of(DEADLINE_CHECK_INTERVAL) {
+            self.deadline_passed();
+        }
+
+        self.stop.load(Ordering::Relaxed)
     }
 
-    false
+    /// Recursively fills the region from the state's next empty cell.
+    ///
+    /// # Returns
+    /// `true` if all remaining presents were successfully placed, `false` if this
+    /// branch has no solution or the search was stopped
+    fn search(&self, state: &mut PackState) -> bool {
+        // Base case: all presents have been placed successfully
+        if state.left == 0 {
+            return true;
+        }
+
+        if self.should_stop(state) {
+            return false;
+        }
+
+        let Some((row, col)) = state.board.first_empty(state.start) else {
+            return false;
+        };
+        let start = state.start;
+        state.start = row * state.board.width + col + 1;
+
+        for &shape_idx in &self.order {
+            if state.remaining[shape_idx] == 0 {
+                continue;
+            }
+
+            for (orientation, mask) in self.masks[shape_idx].iter().enumerate() {
+                if !mask.fits_at(&state.board, row, col) {
+                    continue;
+                }
+
+                mask.toggle_at(&mut state.board, row, col);
+                state.remaining[shape_idx] -= 1;
+                state.left -= 1;
+                state.placements.push(Placement {
+                    shape: shape_idx,
+                    orientation,
+                    row,
+                    col,
+                });
+
+                if self.search(state) {
+                    return true;
+                }
+
+                // Backtrack: this path didn't work, remove the shape
+                state.placements.pop();
+                state.left += 1;
+                state.remaining[shape_idx] += 1;
+                mask.toggle_at(&mut state.board, row, col);
+            }
+        }
+
+        // No present starts here: leave the cell empty if the spare area allows it
+        if state.slack > 0 {
+            state.slack -= 1;
+            state.board.toggle(row, col);
+
+            if self.search(state) {
+                return true;
+            }
+
+            state.board.toggle(row, col);
+            state.slack += 1;
+        }
+
+        state.start = start;
+        false
+    }
+}
+
+/// Labels used to tell presents apart when rendering a packing.
+const LABELS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Renders a packing as ASCII art, one letter per present and `.` for empty cells.
+///
+/// Letters are assigned in placement order and wrap around after `z`.
+fn render_packing(region: &Region, shapes: &[Shape], placements: &[Placement]) -> String {
+    let orientations: Vec<Vec<Shape>> = shapes.iter().map(Shape::orientations).collect();
+    let mut canvas = vec![vec!['.'; region.width()]; region.height()];
+
+    for (i, placement) in placements.iter().enumerate() {
+        let label = char::from(LABELS[i % LABELS.len()]);
+        let shape = &orientations[placement.shape][placement.orientation];
+
+        for (r, c) in shape.cells_at(placement.row, placement.col) {
+            canvas[r][c] = label;
+        }
+    }
+
+    canvas
+        .iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Prints every region along with the packing found for it, if any.
+fn show_packings(data: &Input) {
+    for (i, region) in data.regions.iter().enumerate() {
+        let header = format!("Region {i} ({}x{})", region.width(), region.height());
+
+        match decide_packing(region, &data.shapes, Some(region_deadline())) {
+            Verdict::Fits(placements) => {
+                println!("{header}: fits");
+                println!("{}", render_packing(region, &data.shapes, &placements));
+            }
+            Verdict::Undecided => println!("{header}: undecided after {REGION_TIME_LIMIT:?}"),
+            Verdict::DoesNotFit => {
+                let presents: usize = region.present_counts.iter().sum();
+
+                let most = max_presents_that_fit(region, &data.shapes, Some(region_deadline()));
+                let placed = most.placements().len();
+                match most {
+                    MostPresents::Exact(_) => {
+                        println!("{header}: does not fit, at most {placed} of {presents} presents");
+                    }
+                    MostPresents::AtLeast(_) => {
+                        println!(
+                            "{header}: does not fit, at least {placed} of {presents} presents"
+                        );
+                    }
+                }
+                println!(
+                    "{}",
+                    render_packing(region, &data.shapes, most.placements())
+                );
+            }
+        }
+        println!();
+    }
+}
+
+/// Prints the smallest square region that fits the presents of every region.
+fn show_smallest_squares(data: &Input) {
+    for (i, region) in data.regions.iter().enumerate() {
+        let Some((side, placements)) =
+            smallest_square_region(region, &data.shapes, Some(region_deadline()))
+        else {
+            println!("Region {i}: smallest square undecided after {REGION_TIME_LIMIT:?}");
+            println!();
+            continue;
+        };
+        let square = Region::new(side, side, region.present_counts.clone());
+
+        println!("Region {i}: smallest square is {side}x{side}");
+        println!("{}", render_packing(&square, &data.shapes, &placements));
+        println!();
+    }
 }
 
 fn main() {
-    run_solution!(Day12);
+    // `--show` and `--square` print packings instead of the puzzle answers
+    match std::env::args().nth(1).as_deref() {
+        Some("--show") => show_packings(&Day12.parse_input(include_str!("../input.txt"))),
+        Some("--square") => {
+            show_smallest_squares(&Day12.parse_input(include_str!("../input.txt")));
+        }
+        _ => run_solution!(Day12),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_part1() {
-        let input = "0:
+    const TEST_INPUT: &str = "0:
 ###
 ##.
 ##.
@@ -415,23 +1104,199 @@ mod tests {
 12x5: 1 0 1 0 2 2
 12x5: 1 0 1 0 3 2";
 
+    #[test]
+    fn test_part1() {
+        let day = Day12;
+        let parsed_input = day.parse_input(TEST_INPUT);
+
+        let part1 = day.part1(&parsed_input);
+
+        assert_eq!(
+            part1,
+            Tally {
+                count: 2,
+                undecided: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_find_packing_returns_valid_placement() {
+        let day = Day12;
+        let parsed_input = day.parse_input(TEST_INPUT);
+        let region = &parsed_input.regions[1];
+
+        let Verdict::Fits(placements) = decide_packing(region, &parsed_input.shapes, None) else {
+            panic!("The second region should fit");
+        };
+        assert_eq!(placements.len(), 6);
+
+        let orientations: Vec<Vec<Shape>> = parsed_input
+            .shapes
+            .iter()
+            .map(Shape::orientations)
+            .collect();
+
+        let mut board = Bitboard::new(region.width(), region.height()).unwrap();
+        for placement in &placements {
+            let mask = ShapeMask::from_shape(&orientations[placement.shape][placement.orientation]);
+            assert!(mask.fits_at(&board, placement.row, placement.col));
+            mask.toggle_at(&mut board, placement.row, placement.col);
+        }
+    }
+
+    #[test]
+    fn test_decide_packing_reports_undecided_on_timeout() {
+        let day = Day12;
+        let parsed_input = day.parse_input(TEST_INPUT);
+        let region = &parsed_input.regions[2];
+
+        // The deadline is checked before the search expands anything
+        let verdict = decide_packing(region, &parsed_input.shapes, Some(Instant::now()));
+
+        assert_eq!(verdict, Verdict::Undecided);
+        assert_eq!(
+            Tally {
+                count: 1,
+                undecided: 1
+            }
+            .to_string(),
+            "at least 1 (1 regions undecided)"
+        );
+    }
+
+    #[test]
+    fn test_wide_region_is_undecided() {
+        // Too low for the block layout, so only the exact search could tell
+        let input = "0:
+###
+#..
+###
+
+200x2: 1";
+
         let day = Day12;
         let parsed_input = day.parse_input(input);
+        let region = &parsed_input.regions[0];
 
-        let part1 = day.part1(&parsed_input);
+        assert_eq!(
+            decide_packing(region, &parsed_input.shapes, None),
+            Verdict::Undecided
+        );
+    }
+
+    #[test]
+    fn test_wide_region_keeps_block_layout_presents() {
+        // 66 blocks fit in a row, while the area would leave room for all 70 presents
+        let input = "0:
+###
+#..
+###
+
+200x3: 70
+200x3: 60";
+
+        let day = Day12;
+        let parsed_input = day.parse_input(input);
 
-        assert_eq!(part1, 2);
+        let most = max_presents_that_fit(&parsed_input.regions[0], &parsed_input.shapes, None);
+        assert!(matches!(most, MostPresents::AtLeast(_)));
+        assert_eq!(most.placements().len(), 66);
+
+        // The block layout alone settles the second region
+        let most = max_presents_that_fit(&parsed_input.regions[1], &parsed_input.shapes, None);
+        assert!(matches!(most, MostPresents::Exact(_)));
+        assert_eq!(most.placements().len(), 60);
+
+        assert_eq!(
+            day.part2(&parsed_input),
+            Tally {
+                count: 126,
+                undecided: 1
+            }
+        );
     }
 
     #[test]
-    fn test_part2() {
-        let input = "";
+    fn test_region_size() {
+        let input = "0:
+###
+#..
+###
+
+12x5: 1
+0x5: 0";
+
+        let day = Day12;
+        let parsed_input = day.parse_input(input);
+
+        let region = &parsed_input.regions[0];
+        assert_eq!(
+            (region.width(), region.height(), region.area()),
+            (12, 5, 60)
+        );
+
+        let empty = &parsed_input.regions[1];
+        assert_eq!((empty.width(), empty.height(), empty.area()), (0, 0, 0));
+        assert_eq!(
+            decide_packing(empty, &parsed_input.shapes, None),
+            Verdict::Fits(vec![])
+        );
+    }
+
+    #[test]
+    fn test_render_packing() {
+        let input = "0:
+###
+#..
+###
+
+3x6: 2";
 
         let day = Day12;
         let parsed_input = day.parse_input(input);
+        let region = &parsed_input.regions[0];
+
+        let Verdict::Fits(placements) = decide_packing(region, &parsed_input.shapes, None) else {
+            panic!("Both presents should fit");
+        };
+        let rendered = render_packing(region, &parsed_input.shapes, &placements);
+
+        assert_eq!(rendered, "AAA\nA..\nAAA\nBBB\nB..\nBBB");
+    }
+
+    #[test]
+    fn test_part2() {
+        let day = Day12;
+        let parsed_input = day.parse_input(TEST_INPUT);
 
         let part2 = day.part2(&parsed_input);
 
-        assert_eq!(part2, 0);
+        assert_eq!(
+            part2,
+            Tally {
+                count: 14,
+                undecided: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_smallest_square_region() {
+        let input = "0:
+###
+#..
+###
+
+1x1: 2";
+
+        let day = Day12;
+        let parsed_input = day.parse_input(input);
+
+        let (side, placements) =
+            smallest_square_region(&parsed_input.regions[0], &parsed_input.shapes, None).unwrap();
+
+        assert_eq!(side, 4);
+        assert_eq!(placements.len(), 2);
     }
 }
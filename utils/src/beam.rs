@@ -0,0 +1,426 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::bigint::BigUint;
+use crate::directions::Direction;
+use crate::memo::Memo;
+use crate::parse::{ParseError, char_grid};
+use crate::points::Point;
+
+/// Orientation of a splitter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+/// What a grid cell does to a beam entering it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BeamCell {
+    /// Lets the beam through unchanged
+    Empty,
+    /// Stops the beam
+    Absorber,
+    /// `/` mirror
+    MirrorSlash,
+    /// `\` mirror
+    MirrorBackslash,
+    /// Splits a beam crossing it into two beams leaving both ways along its axis, beams moving
+    /// along the axis pass through (`-` and `|`)
+    Splitter(Axis),
+    /// Sends the beam on from the cells on both sides of it, in its original direction (`^`).
+    /// The side cells don't act on these beams, which move on from them right away, as in the
+    /// tachyon manifold of 2025 day 7
+    SideSplitter,
+}
+
+impl BeamCell {
+    /// Reads the usual symbols: `/`, `\`, `-`, `|`, `^` and `#` for an absorber. Anything else,
+    /// like `.` or a start marker, is empty.
+    #[must_use]
+    pub fn from_char(c: char) -> Self {
+        match c {
+            '/' => BeamCell::MirrorSlash,
+            '\\' => BeamCell::MirrorBackslash,
+            '-' => BeamCell::Splitter(Axis::Horizontal),
+            '|' => BeamCell::Splitter(Axis::Vertical),
+            '^' => BeamCell::SideSplitter,
+            '#' => BeamCell::Absorber,
+            _ => BeamCell::Empty,
+        }
+    }
+
+    /// Returns the beams leaving a cell entered in `direction`, and whether it was split.
+    ///
+    /// Each beam is the side of the cell it appears on (`None` for the cell it moves on from,
+    /// `SideSplitter` beams appearing on its sides) and the direction it then moves in.
+    fn redirect(self, direction: Direction) -> (Vec<(Option<Direction>, Direction)>, bool) {
+        match self {
+            BeamCell::Empty => (vec![(None, direction)], false),
+            BeamCell::Absorber => (Vec::new(), false),
+            BeamCell::MirrorSlash => (vec![(None, reflect_slash(direction))], false),
+            BeamCell::MirrorBackslash => (vec![(None, reflect_backslash(direction))], false),
+            BeamCell::Splitter(axis) => {
+                let along = match axis {
+                    Axis::Horizontal => [Direction::Left, Direction::Right],
+                    Axis::Vertical => [Direction::Up, Direction::Down],
+                };
+                if along.contains(&direction) {
+                    (vec![(None, direction)], false)
+                } else {
+                    (along.map(|out| (None, out)).to_vec(), true)
+                }
+            }
+            BeamCell::SideSplitter => (
+                vec![
+                    (Some(direction.turn_clockwise()), direction),
+                    (Some(direction.turn_counterclockwise()), direction),
+                ],
+                true,
+            ),
+        }
+    }
+}
+
+/// Reflects a direction off a `/` mirror, which beams moving along it pass unchanged.
+fn reflect_slash(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Right,
+        Direction::Right => Direction::Up,
+        Direction::Down => Direction::Left,
+        Direction::Left => Direction::Down,
+        Direction::UpLeft => Direction::DownRight,
+        Direction::DownRight => Direction::UpLeft,
+        Direction::UpRight | Direction::DownLeft => direction,
+    }
+}
+
+/// Reflects a direction off a `\` mirror, which beams moving along it pass unchanged.
+fn reflect_backslash(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Left,
+        Direction::Left => Direction::Up,
+        Direction::Down => Direction::Right,
+        Direction::Right => Direction::Down,
+        Direction::UpRight => Direction::DownLeft,
+        Direction::DownLeft => Direction::UpRight,
+        Direction::UpLeft | Direction::DownRight => direction,
+    }
+}
+
+/// A beam entering a cell, moving in a direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Beam {
+    pub position: Point<usize>,
+    pub direction: Direction,
+}
+
+impl Beam {
+    #[must_use]
+    pub fn new(position: Point<usize>, direction: Direction) -> Self {
+        Beam {
+            position,
+            direction,
+        }
+    }
+}
+
+/// Cells reached by the beams of a `BeamGrid::propagate` run.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Propagation {
+    /// Cells at least one beam went through
+    pub energized: HashSet<Point<usize>>,
+    /// Splitters that split at least one beam
+    pub splitters_hit: HashSet<Point<usize>>,
+}
+
+/// Where a beam goes from the cell it enters.
+struct Step {
+    /// Beams entering the next cells, `None` for those leaving the grid
+    next: Vec<Option<Beam>>,
+    /// Cells the beams went through on the way without them acting, beside a side splitter
+    passed: Vec<Point<usize>>,
+    /// Whether the cell split the beam
+    split: bool,
+}
+
+/// Grid of cells that beams travel through, one cell per step.
+///
+/// The tachyon manifold of 2025 day 7 is such a grid, its `S` beam giving the same answers here
+/// as in the row-by-row pass day 7 solves it with.
+///
+/// Beams meeting in the same cell with the same direction merge into one. A beam leaving the
+/// grid, or stopped by an absorber, ends there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BeamGrid {
+    cells: Vec<Vec<BeamCell>>,
+    width: usize,
+}
+
+impl BeamGrid {
+    /// Creates a grid from rows of cells of the same width.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows don't all have the width of the first one.
+    #[must_use]
+    pub fn new(cells: Vec<Vec<BeamCell>>) -> Self {
+        let width = cells.first().map_or(0, Vec::len);
+        assert!(
+            cells.iter().all(|row| row.len() == width),
+            "beam grid rows must have the same width"
+        );
+
+        BeamGrid { cells, width }
+    }
+
+    /// Parses a char grid, mapping each char to its cell with `BeamCell::from_char`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError::RaggedGrid` if the lines don't all have the same width.
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        Self::parse_with(input, BeamCell::from_char)
+    }
+
+    /// Parses a char grid with a custom mapping from chars to cells.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError::RaggedGrid` if the lines don't all have the same width.
+    pub fn parse_with(input: &str, cell: impl Fn(char) -> BeamCell) -> Result<Self, ParseError> {
+        let cells = char_grid(input)?
+            .into_iter()
+            .map(|row| row.into_iter().map(&cell).collect())
+            .collect();
+
+        Ok(Self::new(cells))
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns the cell at this position, or `None` outside the grid.
+    #[must_use]
+    pub fn get(&self, position: Point<usize>) -> Option<BeamCell> {
+        self.cells.get(position.y)?.get(position.x).copied()
+    }
+
+    /// Follows every beam from `start` until they all leave the grid, are absorbed or loop.
+    #[must_use]
+    pub fn propagate(&self, start: Beam) -> Propagation {
+        let mut propagation = Propagation::default();
+        let mut seen = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(beam) = queue.pop_front() {
+            if self.get(beam.position).is_none() {
+                continue;
+            }
+            propagation.energized.insert(beam.position);
+
+            let Step {
+                next,
+                passed,
+                split,
+            } = self.step(beam);
+            if split {
+                propagation.splitters_hit.insert(beam.position);
+            }
+            propagation.energized.extend(passed);
+            for next in next.into_iter().flatten() {
+                if seen.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        propagation
+    }
+
+    /// Counts the distinct paths a particle can take from `start`, taking every branch at each
+    /// splitter, until it leaves the grid or is absorbed.
+    ///
+    /// Returns `None` if a path loops, as there are infinitely many of them then.
+    #[must_use]
+    pub fn count_timelines(&self, start: Beam) -> Option<BigUint> {
+        if self.get(start.position).is_none() {
+            return Some(BigUint::from(1_u8));
+        }
+
+        // Paths merge when beams do, so the count from a beam is memoized, without recursion
+        // for grids too tall to recurse through. A beam leaving the grid is `None`, and both it
+        // and an absorbed beam, which has no next beams, end a single path
+        Memo::new().get_or_compute_iterative(
+            Some(start),
+            |beam| beam.map_or_else(Vec::new, |beam| self.step(beam).next),
+            |_, counts| {
+                if counts.is_empty() {
+                    BigUint::from(1_u8)
+                } else {
+                    counts.iter().sum()
+                }
+            },
+        )
+    }
+
+    /// Moves `beam` through the cell it enters.
+    fn step(&self, beam: Beam) -> Step {
+        let mut step = Step {
+            next: Vec::new(),
+            passed: Vec::new(),
+            split: false,
+        };
+        let Some(cell) = self.get(beam.position) else {
+            return step;
+        };
+        let (outgoing, split) = cell.redirect(beam.direction);
+        step.split = split;
+
+        for (side, direction) in outgoing {
+            let from = match side {
+                // Side beams pass through the cell next to the splitter without it acting on
+                // them, and the next cell is the first one that does
+                Some(side) => {
+                    let Some(side_cell) = self.neighbor(beam.position, side) else {
+                        step.next.push(None);
+                        continue;
+                    };
+                    step.passed.push(side_cell);
+                    side_cell
+                }
+                None => beam.position,
+            };
+
+            let next = self.neighbor(from, direction);
+            step.next
+                .push(next.map(|position| Beam::new(position, direction)));
+        }
+
+        step
+    }
+
+    fn neighbor(&self, position: Point<usize>, direction: Direction) -> Option<Point<usize>> {
+        position
+            .checked_step(direction, 1)
+            .filter(|next| next.x < self.width && next.y < self.height())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The tachyon manifold of 2025 day 7
+    const MANIFOLD: &str = ".......S.......
+...............
+.......^.......
+...............
+......^.^......
+...............
+.....^.^.^.....
+...............
+....^.^...^....
+...............
+...^.^...^.^...
+...............
+..^...^.....^..
+...............
+.^.^.^.^.^...^.
+...............";
+
+    /// The mirror maze of 2023 day 16
+    const CONTRAPTION: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....";
+
+    #[test]
+    fn test_manifold() {
+        let grid = BeamGrid::parse(MANIFOLD).unwrap();
+        let start = Beam::new(Point::new(7, 0), Direction::Down);
+
+        assert_eq!(grid.propagate(start).splitters_hit.len(), 21);
+        assert_eq!(grid.count_timelines(start), Some(BigUint::from(40_u8)));
+    }
+
+    #[test]
+    fn test_mirror_maze() {
+        let grid = BeamGrid::parse(CONTRAPTION).unwrap();
+        let start = Beam::new(Point::new(0, 0), Direction::Right);
+
+        assert_eq!(grid.propagate(start).energized.len(), 46);
+
+        let best = (0..grid.height())
+            .map(|y| Beam::new(Point::new(0, y), Direction::Right))
+            .chain((0..grid.width()).map(|x| Beam::new(Point::new(x, 0), Direction::Down)))
+            .map(|start| grid.propagate(start).energized.len())
+            .max();
+        assert_eq!(best, Some(51));
+
+        // The beams of the maze go around in circles
+        assert_eq!(grid.count_timelines(start), None);
+    }
+
+    #[test]
+    fn test_adjacent_side_splitters() {
+        // The right beam of the first `^` goes down from the second one, which doesn't split it
+        let grid = BeamGrid::parse(".S..\n.^^.\n....").unwrap();
+        let start = Beam::new(Point::new(1, 0), Direction::Down);
+
+        let propagation = grid.propagate(start);
+        assert_eq!(propagation.splitters_hit, HashSet::from([Point::new(1, 1)]));
+        assert!(propagation.energized.contains(&Point::new(2, 1)));
+        assert!(propagation.energized.contains(&Point::new(2, 2)));
+        assert_eq!(grid.count_timelines(start), Some(BigUint::from(2_u8)));
+    }
+
+    #[test]
+    fn test_cells() {
+        let mirrors = [
+            (BeamCell::MirrorSlash, Direction::Right, Direction::Up),
+            (BeamCell::MirrorSlash, Direction::Down, Direction::Left),
+            (BeamCell::MirrorBackslash, Direction::Right, Direction::Down),
+            (BeamCell::MirrorBackslash, Direction::Up, Direction::Left),
+        ];
+        for (cell, incoming, outgoing) in mirrors {
+            assert_eq!(cell.redirect(incoming), (vec![(None, outgoing)], false));
+        }
+
+        let splitter = BeamCell::Splitter(Axis::Vertical);
+        assert_eq!(
+            splitter.redirect(Direction::Up),
+            (vec![(None, Direction::Up)], false)
+        );
+        assert!(splitter.redirect(Direction::Left).1);
+
+        // An absorbed beam ends one timeline
+        let grid = BeamGrid::parse("..#\n/.^").unwrap();
+        let start = Beam::new(Point::new(0, 0), Direction::Right);
+        assert_eq!(
+            grid.propagate(start).energized,
+            HashSet::from([Point::new(0, 0), Point::new(1, 0), Point::new(2, 0)])
+        );
+        assert_eq!(grid.count_timelines(start), Some(BigUint::from(1_u8)));
+
+        // The `^` sends one beam off the bottom, and the other through the absorber above it,
+        // which doesn't act on side beams, and off the right edge
+        let start = Beam::new(Point::new(1, 1), Direction::Right);
+        let propagation = grid.propagate(start);
+        assert_eq!(propagation.splitters_hit, HashSet::from([Point::new(2, 1)]));
+        assert!(propagation.energized.contains(&Point::new(2, 0)));
+        assert_eq!(grid.count_timelines(start), Some(BigUint::from(2_u8)));
+    }
+}
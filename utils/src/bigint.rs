@@ -0,0 +1,661 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::parse::ParseError;
+
+/// Arbitrary-precision unsigned integer, for answers that don't fit a primitive type.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Base 2^32 digits, least significant first, without trailing zeros
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub const ZERO: BigUint = BigUint { limbs: Vec::new() };
+
+    #[must_use]
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    /// Number of bits needed to write the value (0 for zero).
+    #[must_use]
+    pub fn bits(&self) -> u64 {
+        self.limbs.last().map_or(0, |&top| {
+            (self.limbs.len() as u64 - 1) * 32 + u64::from(32 - top.leading_zeros())
+        })
+    }
+
+    /// Returns the value as a `u128`, or `None` if it's too large.
+    #[must_use]
+    pub fn to_u128(&self) -> Option<u128> {
+        (self.limbs.len() <= 4).then(|| {
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0, |acc, &limb| (acc << 32) | u128::from(limb))
+        })
+    }
+
+    /// Subtracts, or returns `None` if `other` is larger.
+    #[must_use]
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if self < other {
+            return None;
+        }
+
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0;
+        for (i, &limb) in self.limbs.iter().enumerate() {
+            let subtrahend = u64::from(other.limbs.get(i).copied().unwrap_or(0)) + borrow;
+            let (diff, underflow) = u64::from(limb).overflowing_sub(subtrahend);
+            limbs.push(diff as u32);
+            borrow = u64::from(underflow);
+        }
+
+        Some(BigUint { limbs }.normalized())
+    }
+
+    /// Returns the quotient and remainder, or `None` when dividing by zero.
+    #[must_use]
+    pub fn div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        if self < divisor {
+            return Some((BigUint::ZERO, self.clone()));
+        }
+        if let [small] = divisor.limbs[..] {
+            let (quotient, remainder) = self.div_rem_small(small);
+            return Some((quotient, BigUint::from(remainder)));
+        }
+
+        // Schoolbook binary long division
+        let mut quotient = vec![0; self.limbs.len()];
+        let mut remainder = BigUint::ZERO;
+        for bit in (0..self.bits()).rev() {
+            remainder = remainder.shl1();
+            if self.bit(bit) {
+                remainder.set_bit0();
+            }
+            if remainder >= *divisor {
+                remainder = remainder.checked_sub(divisor)?;
+                quotient[(bit / 32) as usize] |= 1 << (bit % 32);
+            }
+        }
+
+        Some((BigUint { limbs: quotient }.normalized(), remainder))
+    }
+
+    /// Raises to the power `exp` by repeated squaring.
+    #[must_use]
+    pub fn pow(&self, mut exp: u32) -> Self {
+        let mut base = self.clone();
+        let mut result = BigUint::from(1_u8);
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result = &result * &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+
+        result
+    }
+
+    fn normalized(mut self) -> Self {
+        while self.limbs.last() == Some(&0) {
+            self.limbs.pop();
+        }
+        self
+    }
+
+    fn bit(&self, bit: u64) -> bool {
+        self.limbs
+            .get((bit / 32) as usize)
+            .is_some_and(|limb| (limb >> (bit % 32)) & 1 == 1)
+    }
+
+    fn set_bit0(&mut self) {
+        match self.limbs.first_mut() {
+            Some(limb) => *limb |= 1,
+            None => self.limbs.push(1),
+        }
+    }
+
+    fn shl1(&self) -> Self {
+        let mut carry = 0;
+        let mut limbs: Vec<u32> = self
+            .limbs
+            .iter()
+            .map(|&limb| {
+                let shifted = (limb << 1) | carry;
+                carry = limb >> 31;
+                shifted
+            })
+            .collect();
+        if carry > 0 {
+            limbs.push(carry);
+        }
+
+        BigUint { limbs }
+    }
+
+    fn div_rem_small(&self, divisor: u32) -> (Self, u32) {
+        let mut limbs = vec![0; self.limbs.len()];
+        let mut remainder = 0_u64;
+
+        for (i, &limb) in self.limbs.iter().enumerate().rev() {
+            let current = (remainder << 32) | u64::from(limb);
+            limbs[i] = (current / u64::from(divisor)) as u32;
+            remainder = current % u64::from(divisor);
+        }
+
+        (BigUint { limbs }.normalized(), remainder as u32)
+    }
+
+    /// Computes `self * factor + addend` in place.
+    fn mul_add_small(&mut self, factor: u32, addend: u32) {
+        let mut carry = u64::from(addend);
+        for limb in &mut self.limbs {
+            let current = u64::from(*limb) * u64::from(factor) + carry;
+            *limb = current as u32;
+            carry = current >> 32;
+        }
+        if carry > 0 {
+            self.limbs.push(carry as u32);
+        }
+    }
+
+    fn add_ref(&self, other: &Self) -> Self {
+        let (long, short) = if self.limbs.len() >= other.limbs.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        let mut limbs = Vec::with_capacity(long.limbs.len() + 1);
+        let mut carry = 0;
+        for (i, &limb) in long.limbs.iter().enumerate() {
+            let sum = u64::from(limb) + u64::from(short.limbs.get(i).copied().unwrap_or(0)) + carry;
+            limbs.push(sum as u32);
+            carry = sum >> 32;
+        }
+        if carry > 0 {
+            limbs.push(carry as u32);
+        }
+
+        BigUint { limbs }
+    }
+
+    fn mul_ref(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::ZERO;
+        }
+
+        let mut limbs = vec![0; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let current = u64::from(a) * u64::from(b) + u64::from(limbs[i + j]) + carry;
+                limbs[i + j] = current as u32;
+                carry = current >> 32;
+            }
+            limbs[i + other.limbs.len()] = carry as u32;
+        }
+
+        BigUint { limbs }.normalized()
+    }
+
+    /// Decimal digits of the value.
+    fn to_decimal(&self) -> String {
+        const CHUNK: u32 = 1_000_000_000;
+
+        let mut chunks = Vec::new();
+        let mut rest = self.clone();
+        while !rest.is_zero() {
+            let (quotient, chunk) = rest.div_rem_small(CHUNK);
+            chunks.push(chunk);
+            rest = quotient;
+        }
+
+        let mut digits = chunks.pop().unwrap_or(0).to_string();
+        for chunk in chunks.iter().rev() {
+            digits.push_str(&format!("{chunk:09}"));
+        }
+        digits
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(value: $t) -> Self {
+                    let mut value = value as u128;
+                    let mut limbs = Vec::new();
+                    while value > 0 {
+                        limbs.push(value as u32);
+                        value >>= 32;
+                    }
+                    BigUint { limbs }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(true, "", &self.to_decimal())
+    }
+}
+
+/// Parses decimal digits, without sign.
+impl FromStr for BigUint {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseError::InvalidNumber(s.to_string()));
+        }
+
+        let mut value = BigUint::ZERO;
+        for digit in s.bytes() {
+            value.mul_add_small(10, u32::from(digit - b'0'));
+        }
+
+        Ok(value.normalized())
+    }
+}
+
+/// Arbitrary-precision signed integer: a sign and a `BigUint` magnitude.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    /// Never set for zero
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    pub const ZERO: BigInt = BigInt {
+        negative: false,
+        magnitude: BigUint::ZERO,
+    };
+
+    fn from_parts(negative: bool, magnitude: BigUint) -> Self {
+        BigInt {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    #[must_use]
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    #[must_use]
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// The absolute value.
+    #[must_use]
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    /// Returns the value as an `i128`, or `None` if it's out of range.
+    #[must_use]
+    pub fn to_i128(&self) -> Option<i128> {
+        let magnitude = self.magnitude.to_u128()?;
+        if self.negative {
+            0_i128.checked_sub_unsigned(magnitude)
+        } else {
+            i128::try_from(magnitude).ok()
+        }
+    }
+
+    /// Returns the quotient rounded toward zero and the remainder (with the sign of `self`),
+    /// like the primitive integers do, or `None` when dividing by zero.
+    #[must_use]
+    pub fn div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+        let (quotient, remainder) = self.magnitude.div_rem(&divisor.magnitude)?;
+
+        Some((
+            BigInt::from_parts(self.negative != divisor.negative, quotient),
+            BigInt::from_parts(self.negative, remainder),
+        ))
+    }
+
+    /// Raises to the power `exp`.
+    #[must_use]
+    pub fn pow(&self, exp: u32) -> Self {
+        BigInt::from_parts(self.negative && exp % 2 == 1, self.magnitude.pow(exp))
+    }
+
+    fn add_ref(&self, other: &Self) -> Self {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, self.magnitude.add_ref(&other.magnitude));
+        }
+
+        // Opposite signs: the larger magnitude wins
+        match self.magnitude.cmp(&other.magnitude) {
+            Ordering::Less => BigInt::from_parts(
+                other.negative,
+                other
+                    .magnitude
+                    .checked_sub(&self.magnitude)
+                    .unwrap_or_default(),
+            ),
+            _ => BigInt::from_parts(
+                self.negative,
+                self.magnitude
+                    .checked_sub(&other.magnitude)
+                    .unwrap_or_default(),
+            ),
+        }
+    }
+
+    fn mul_ref(&self, other: &Self) -> Self {
+        BigInt::from_parts(
+            self.negative != other.negative,
+            self.magnitude.mul_ref(&other.magnitude),
+        )
+    }
+}
+
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(value: $t) -> Self {
+                    BigInt::from_parts(value < 0, BigUint::from(value.unsigned_abs()))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_signed!(i8, i16, i32, i64, i128, isize);
+
+macro_rules! impl_from_unsigned_for_bigint {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(value: $t) -> Self {
+                    BigInt::from(BigUint::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned_for_bigint!(u8, u16, u32, u64, u128, usize);
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        BigInt {
+            negative: false,
+            magnitude,
+        }
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.negative, "", &self.magnitude.to_decimal())
+    }
+}
+
+/// Parses decimal digits with an optional leading `-`.
+impl FromStr for BigInt {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s),
+        };
+        let magnitude = digits
+            .parse()
+            .map_err(|_| ParseError::InvalidNumber(s.to_string()))?;
+
+        Ok(BigInt::from_parts(negative, magnitude))
+    }
+}
+
+impl Neg for BigInt {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        BigInt::from_parts(!self.negative, self.magnitude)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+/// Implements a binary operator for owned and borrowed operands, from a `fn(&T, &T) -> T`.
+macro_rules! impl_binary_op {
+    ($t:ty, $op:ident, $method:ident, $assign_op:ident, $assign_method:ident, $f:expr) => {
+        impl $op<&$t> for &$t {
+            type Output = $t;
+
+            fn $method(self, rhs: &$t) -> $t {
+                $f(self, rhs)
+            }
+        }
+
+        impl $op for $t {
+            type Output = $t;
+
+            fn $method(self, rhs: $t) -> $t {
+                $f(&self, &rhs)
+            }
+        }
+
+        impl $op<&$t> for $t {
+            type Output = $t;
+
+            fn $method(self, rhs: &$t) -> $t {
+                $f(&self, rhs)
+            }
+        }
+
+        impl $assign_op<&$t> for $t {
+            fn $assign_method(&mut self, rhs: &$t) {
+                *self = $f(self, rhs);
+            }
+        }
+
+        impl $assign_op for $t {
+            fn $assign_method(&mut self, rhs: $t) {
+                *self = $f(self, &rhs);
+            }
+        }
+    };
+}
+
+impl_binary_op!(BigUint, Add, add, AddAssign, add_assign, BigUint::add_ref);
+impl_binary_op!(BigUint, Mul, mul, MulAssign, mul_assign, BigUint::mul_ref);
+impl_binary_op!(
+    BigUint,
+    Sub,
+    sub,
+    SubAssign,
+    sub_assign,
+    |a: &BigUint, b: &BigUint| a.checked_sub(b).expect("attempt to subtract with overflow")
+);
+impl_binary_op!(BigInt, Add, add, AddAssign, add_assign, BigInt::add_ref);
+impl_binary_op!(BigInt, Mul, mul, MulAssign, mul_assign, BigInt::mul_ref);
+impl_binary_op!(
+    BigInt,
+    Sub,
+    sub,
+    SubAssign,
+    sub_assign,
+    |a: &BigInt, b: &BigInt| a.add_ref(&-b)
+);
+
+macro_rules! impl_sum_product {
+    ($($t:ty),*) => {
+        $(
+            impl Sum for $t {
+                fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                    iter.fold(<$t>::ZERO, |acc, value| acc + value)
+                }
+            }
+
+            impl<'a> Sum<&'a $t> for $t {
+                fn sum<I: Iterator<Item = &'a $t>>(iter: I) -> Self {
+                    iter.fold(<$t>::ZERO, |acc, value| acc + value)
+                }
+            }
+
+            impl Product for $t {
+                fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+                    iter.fold(<$t>::from(1_u8), |acc, value| acc * value)
+                }
+            }
+
+            impl<'a> Product<&'a $t> for $t {
+                fn product<I: Iterator<Item = &'a $t>>(iter: I) -> Self {
+                    iter.fold(<$t>::from(1_u8), |acc, value| acc * value)
+                }
+            }
+        )*
+    };
+}
+
+impl_sum_product!(BigUint, BigInt);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(s: &str) -> BigUint {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_display_and_parse() {
+        assert_eq!(BigUint::ZERO.to_string(), "0");
+        assert_eq!(BigUint::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(big("0001000000000").to_string(), "1000000000");
+        assert_eq!(format!("{:>6}", BigUint::from(42_u8)), "    42");
+
+        let digits = "123456789012345678901234567890123456789012345678901234567890";
+        assert_eq!(big(digits).to_string(), digits);
+
+        assert!("".parse::<BigUint>().is_err());
+        assert!("-1".parse::<BigUint>().is_err());
+        assert_eq!("-0".parse::<BigInt>(), Ok(BigInt::ZERO));
+        assert_eq!("-17".parse::<BigInt>().unwrap().to_string(), "-17");
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let max = BigUint::from(u128::MAX);
+        let one = BigUint::from(1_u8);
+        assert_eq!(
+            (&max + &one).to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!((&max + &one) - one, max);
+        assert_eq!(max.to_u128(), Some(u128::MAX));
+        assert_eq!((&max + &max).to_u128(), None);
+        assert_eq!(BigUint::from(2_u8).pow(128).bits(), 129);
+
+        let factorial: BigUint = (1..=30_u32).map(BigUint::from).product();
+        assert_eq!(factorial.to_string(), "265252859812191058636308480000000");
+        assert_eq!(BigUint::from(5_u8).checked_sub(&BigUint::from(7_u8)), None);
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let factorial = big("265252859812191058636308480000000");
+        let divisor = big("1307674368000"); // 15!
+
+        let (quotient, remainder) = factorial.div_rem(&divisor).unwrap();
+        assert_eq!(quotient.to_string(), "202843204931727360000");
+        assert!(remainder.is_zero());
+
+        let (quotient, remainder) = (&factorial + &BigUint::from(7_u8))
+            .div_rem(&divisor)
+            .unwrap();
+        assert_eq!(quotient.to_string(), "202843204931727360000");
+        assert_eq!(remainder, BigUint::from(7_u8));
+
+        assert_eq!(factorial.div_rem(&BigUint::ZERO), None);
+    }
+
+    #[test]
+    fn test_signed() {
+        let a = BigInt::from(-7);
+        let b = BigInt::from(3);
+
+        assert_eq!(&a + &b, BigInt::from(-4));
+        assert_eq!(&b - &a, BigInt::from(10));
+        assert_eq!(&a * &b, BigInt::from(-21));
+        assert_eq!(&a + &BigInt::from(7), BigInt::ZERO);
+        assert!(!(&a + &BigInt::from(7)).is_negative());
+        assert_eq!(a.pow(3), BigInt::from(-343));
+        assert_eq!(
+            a.div_rem(&b),
+            Some((BigInt::from(-7 / 3), BigInt::from(-7 % 3)))
+        );
+        assert!(a < b && BigInt::from(-8) < a);
+        assert_eq!(BigInt::from(i128::MIN).to_i128(), Some(i128::MIN));
+        assert_eq!((BigInt::from(i128::MIN) - &b).to_i128(), None);
+
+        let total: BigInt = [a, b, BigInt::from(u128::MAX)].iter().sum();
+        assert_eq!(total.to_string(), (u128::MAX - 4).to_string());
+    }
+}
@@ -0,0 +1,235 @@
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::parse::ParseError;
+
+/// Block of text read as a grid of chars, split into groups of columns by the columns that are
+/// blank on every line.
+///
+/// Lines may have different lengths: a short line counts as blank past its end. Columns are
+/// counted in chars, so non-ASCII text lines up as it reads.
+#[derive(Debug, Clone)]
+pub struct ColumnTable<'a> {
+    lines: Vec<&'a str>,
+    /// Byte offset and char of every column of each line, for O(1) access
+    chars: Vec<Vec<(usize, char)>>,
+    width: usize,
+}
+
+impl<'a> ColumnTable<'a> {
+    /// Reads the lines of `text`, leaving out trailing blank lines.
+    #[must_use]
+    pub fn new(text: &'a str) -> Self {
+        let mut lines: Vec<&str> = text.lines().collect();
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+
+        let chars: Vec<Vec<(usize, char)>> = lines
+            .iter()
+            .map(|line| line.char_indices().collect())
+            .collect();
+        let width = chars.iter().map(Vec::len).max().unwrap_or(0);
+
+        ColumnTable {
+            lines,
+            chars,
+            width,
+        }
+    }
+
+    /// Number of columns of the longest line.
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of lines.
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns the char at this position, or `None` past the end of the line.
+    #[must_use]
+    pub fn char_at(&self, row: usize, col: usize) -> Option<char> {
+        self.chars.get(row)?.get(col).map(|&(_, c)| c)
+    }
+
+    /// Returns `true` if the column holds only whitespace on every line.
+    #[must_use]
+    pub fn is_blank_column(&self, col: usize) -> bool {
+        (0..self.height()).all(|row| self.char_at(row, col).is_none_or(char::is_whitespace))
+    }
+
+    /// Splits the table into the maximal runs of non-blank columns, from left to right.
+    #[must_use]
+    pub fn groups(&self) -> Vec<ColumnGroup<'_>> {
+        let mut groups = Vec::new();
+        let mut start = None;
+
+        for col in 0..=self.width {
+            let blank = col == self.width || self.is_blank_column(col);
+            match (start, blank) {
+                (None, false) => start = Some(col),
+                (Some(first), true) => {
+                    groups.push(ColumnGroup {
+                        table: self,
+                        columns: first..col,
+                    });
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+
+        groups
+    }
+
+    /// Returns the part of a line within these columns, cut short if the line is.
+    fn slice(&self, row: usize, columns: &Range<usize>) -> &'a str {
+        let line = self.lines[row];
+        let offset = |col: usize| self.chars[row].get(col).map_or(line.len(), |&(i, _)| i);
+
+        &line[offset(columns.start)..offset(columns.end)]
+    }
+}
+
+/// Run of adjacent columns of a `ColumnTable` with a blank column (or the edge) on each side.
+#[derive(Debug, Clone)]
+pub struct ColumnGroup<'a> {
+    table: &'a ColumnTable<'a>,
+    columns: Range<usize>,
+}
+
+impl<'a> ColumnGroup<'a> {
+    /// The table columns covered by the group.
+    #[must_use]
+    pub fn columns(&self) -> Range<usize> {
+        self.columns.clone()
+    }
+
+    /// Returns the text of a line within the group, trimmed (empty for lines too short).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `row` is not a line of the table.
+    #[must_use]
+    pub fn cell(&self, row: usize) -> &'a str {
+        self.table.slice(row, &self.columns).trim()
+    }
+
+    /// Reads one number per line of `rows`, the usual way, skipping lines blank in the group.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError::InvalidNumber` for a cell that doesn't parse as `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rows` goes past the last line of the table.
+    pub fn row_numbers<T: FromStr>(&self, rows: Range<usize>) -> Result<Vec<T>, ParseError> {
+        rows.map(|row| self.cell(row))
+            .filter(|cell| !cell.is_empty())
+            .map(|cell| {
+                cell.parse()
+                    .map_err(|_| ParseError::InvalidNumber(cell.to_string()))
+            })
+            .collect()
+    }
+
+    /// Reads one number per column, right to left, from the digits of `rows` read top to bottom
+    /// (top digit most significant). Columns without digits are skipped, other chars ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParseError::InvalidNumber` for a number that doesn't fit `T`.
+    pub fn column_numbers<T: FromStr>(&self, rows: Range<usize>) -> Result<Vec<T>, ParseError> {
+        self.columns
+            .clone()
+            .rev()
+            .map(|col| {
+                rows.clone()
+                    .filter_map(|row| self.table.char_at(row, col))
+                    .filter(char::is_ascii_digit)
+                    .collect::<String>()
+            })
+            .filter(|digits| !digits.is_empty())
+            .map(|digits| {
+                digits
+                    .parse()
+                    .map_err(|_| ParseError::InvalidNumber(digits))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The day 6 worksheet, operator line included
+    const WORKSHEET: &str = "123 328  51 64
+ 45 64  387 23
+  6 98  215 314
+*   +   *   +  ";
+
+    #[test]
+    fn test_groups() {
+        let table = ColumnTable::new(WORKSHEET);
+        assert_eq!(table.height(), 4);
+        assert_eq!(table.width(), 15);
+
+        let groups = table.groups();
+        let columns: Vec<Range<usize>> = groups.iter().map(ColumnGroup::columns).collect();
+        assert_eq!(columns, vec![0..3, 4..7, 8..11, 12..15]);
+
+        assert_eq!(groups[1].cell(1), "64");
+        assert_eq!(groups[3].cell(3), "+");
+    }
+
+    #[test]
+    fn test_row_numbers() {
+        let table = ColumnTable::new(WORKSHEET);
+        let groups = table.groups();
+
+        assert_eq!(groups[0].row_numbers(0..3), Ok(vec![123, 45, 6]));
+        assert_eq!(groups[3].row_numbers(0..3), Ok(vec![64, 23, 314]));
+        assert_eq!(
+            groups[0].row_numbers::<u32>(0..4),
+            Err(ParseError::InvalidNumber("*".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_column_numbers() {
+        let table = ColumnTable::new(WORKSHEET);
+        let groups = table.groups();
+
+        assert_eq!(groups[0].column_numbers(0..3), Ok(vec![356, 24, 1]));
+        assert_eq!(groups[3].column_numbers(0..3), Ok(vec![4, 431, 623]));
+        assert_eq!(
+            groups[2].column_numbers::<u8>(0..3),
+            Err(ParseError::InvalidNumber("581".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_ragged_and_non_ascii() {
+        // The second line stops early, and multi-byte chars take one column each
+        let table = ColumnTable::new("é1 22\n7\n€3 4\n\n");
+        assert_eq!(table.height(), 3);
+        assert_eq!(table.char_at(1, 1), None);
+        assert_eq!(table.char_at(2, 0), Some('€'));
+
+        let groups = table.groups();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].cell(0), "é1");
+        assert_eq!(groups[0].cell(1), "7");
+        assert_eq!(groups[1].cell(1), "");
+        assert_eq!(groups[1].row_numbers(0..3), Ok(vec![22, 4]));
+        assert_eq!(groups[0].column_numbers(0..3), Ok(vec![13, 7]));
+
+        assert!(ColumnTable::new("").groups().is_empty());
+    }
+}
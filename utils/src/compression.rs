@@ -0,0 +1,93 @@
+use crate::points::Number;
+
+/// Maps a set of sparse coordinates onto the dense indices `0..len`, preserving their order.
+///
+/// Useful when only the relative order of a few coordinates matters but their values are huge,
+/// e.g. to build a grid over the distinct x and y values of a polygon's vertices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoordinateCompression<T: Number + Ord> {
+    values: Vec<T>,
+}
+
+impl<T: Number + Ord> CoordinateCompression<T> {
+    /// Builds the compression from any values; duplicates are merged.
+    pub fn new(values: impl IntoIterator<Item = T>) -> Self {
+        let mut values: Vec<T> = values.into_iter().collect();
+        values.sort_unstable();
+        values.dedup();
+
+        CoordinateCompression { values }
+    }
+
+    /// Returns the number of distinct values.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the distinct values in increasing order.
+    #[must_use]
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Returns the compressed index of `value`, if it is one of the compressed values.
+    #[must_use]
+    pub fn index(&self, value: T) -> Option<usize> {
+        self.search(value).ok()
+    }
+
+    /// Returns the number of compressed values strictly smaller than `value`.
+    #[must_use]
+    pub fn rank(&self, value: T) -> usize {
+        match self.search(value) {
+            Ok(index) | Err(index) => index,
+        }
+    }
+
+    /// Returns the value at a compressed index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[must_use]
+    pub fn value(&self, index: usize) -> T {
+        self.values[index]
+    }
+
+    fn search(&self, value: T) -> Result<usize, usize> {
+        self.values.binary_search(&value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression() {
+        let compression = CoordinateCompression::new([100, 5, 42, 5, 1_000_000]);
+
+        assert_eq!(compression.len(), 4);
+        assert_eq!(compression.values(), &[5, 42, 100, 1_000_000]);
+        assert_eq!(compression.index(42), Some(1));
+        assert_eq!(compression.index(43), None);
+        assert_eq!(compression.value(3), 1_000_000);
+    }
+
+    #[test]
+    fn test_rank() {
+        let compression = CoordinateCompression::new([10, 20, 30]);
+
+        assert_eq!(compression.rank(5), 0);
+        assert_eq!(compression.rank(10), 0);
+        assert_eq!(compression.rank(15), 1);
+        assert_eq!(compression.rank(30), 2);
+        assert_eq!(compression.rank(35), 3);
+    }
+}
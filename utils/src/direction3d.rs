@@ -0,0 +1,196 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Neg};
+
+use crate::point3d::Point3D;
+use crate::points::Number;
+
+/// The 6 axis-aligned directions in 3D, right-handed with `z` pointing up.
+#[derive(Debug, PartialEq, Clone, Copy, Hash, Eq)]
+pub enum Direction3D {
+    /// `+x`
+    East,
+    /// `-x`
+    West,
+    /// `+y`
+    North,
+    /// `-y`
+    South,
+    /// `+z`
+    Up,
+    /// `-z`
+    Down,
+}
+
+impl Direction3D {
+    /// Returns the 6 directions, in `+x`, `-x`, `+y`, `-y`, `+z`, `-z` order.
+    #[must_use]
+    pub fn all() -> [Direction3D; 6] {
+        [
+            Direction3D::East,
+            Direction3D::West,
+            Direction3D::North,
+            Direction3D::South,
+            Direction3D::Up,
+            Direction3D::Down,
+        ]
+    }
+
+    #[must_use]
+    pub fn opposite(&self) -> Direction3D {
+        match self {
+            Direction3D::East => Direction3D::West,
+            Direction3D::West => Direction3D::East,
+            Direction3D::North => Direction3D::South,
+            Direction3D::South => Direction3D::North,
+            Direction3D::Up => Direction3D::Down,
+            Direction3D::Down => Direction3D::Up,
+        }
+    }
+
+    /// Returns the `(dx, dy, dz)` unit step of the direction.
+    #[must_use]
+    pub fn delta(&self) -> (isize, isize, isize) {
+        match self {
+            Direction3D::East => (1, 0, 0),
+            Direction3D::West => (-1, 0, 0),
+            Direction3D::North => (0, 1, 0),
+            Direction3D::South => (0, -1, 0),
+            Direction3D::Up => (0, 0, 1),
+            Direction3D::Down => (0, 0, -1),
+        }
+    }
+}
+
+impl fmt::Display for Direction3D {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Direction3D::East => "east",
+            Direction3D::West => "west",
+            Direction3D::North => "north",
+            Direction3D::South => "south",
+            Direction3D::Up => "up",
+            Direction3D::Down => "down",
+        };
+
+        f.write_str(name)
+    }
+}
+
+// direction to a unit step on a signed point
+impl<T: Number + Neg<Output = T>> From<Direction3D> for Point3D<T> {
+    fn from(d: Direction3D) -> Self {
+        let unit = |delta: isize| match delta {
+            1 => T::ONE,
+            -1 => -T::ONE,
+            _ => T::ZERO,
+        };
+        let (dx, dy, dz) = d.delta();
+
+        Point3D::new(unit(dx), unit(dy), unit(dz))
+    }
+}
+
+impl<T: Number + Neg<Output = T>> Add<Direction3D> for Point3D<T> {
+    type Output = Point3D<T>;
+
+    fn add(self, d: Direction3D) -> Self::Output {
+        self + Point3D::from(d)
+    }
+}
+
+impl<T: Number + Neg<Output = T>> AddAssign<Direction3D> for Point3D<T> {
+    fn add_assign(&mut self, d: Direction3D) {
+        *self = *self + d;
+    }
+}
+
+impl<T: Number + Neg<Output = T>> Point3D<T> {
+    /// Moves `n` steps in the given direction.
+    #[must_use]
+    pub fn step(&self, direction: Direction3D, n: T) -> Self {
+        *self + Point3D::from(direction) * n
+    }
+
+    /// Returns the 6 face neighbors, in the order of `Direction3D::all`.
+    #[must_use]
+    pub fn neighbors6(&self) -> [Self; 6] {
+        Direction3D::all().map(|d| *self + d)
+    }
+
+    /// Returns the 26 neighbors sharing a face, an edge or a corner, ordered by `x`, then `y`,
+    /// then `z` offset.
+    #[must_use]
+    pub fn neighbors26(&self) -> [Self; 26] {
+        let units = [-T::ONE, T::ZERO, T::ONE];
+        let mut neighbors = [*self; 26];
+        let mut count = 0;
+
+        for dx in units {
+            for dy in units {
+                for dz in units {
+                    if dx != T::ZERO || dy != T::ZERO || dz != T::ZERO {
+                        neighbors[count] = *self + Point3D::new(dx, dy, dz);
+                        count += 1;
+                    }
+                }
+            }
+        }
+
+        neighbors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_directions() {
+        for direction in Direction3D::all() {
+            assert_eq!(direction.opposite().opposite(), direction);
+            assert_eq!(
+                Point3D::<i32>::from(direction) + direction.opposite(),
+                Point3D::new(0, 0, 0)
+            );
+        }
+
+        assert_eq!(Direction3D::Up.to_string(), "up");
+        assert_eq!(
+            Point3D::<i64>::from(Direction3D::South),
+            Point3D::new(0, -1, 0)
+        );
+
+        // Right-handed: east x north = up
+        let east = Point3D::<i32>::from(Direction3D::East);
+        let north = Point3D::<i32>::from(Direction3D::North);
+        assert_eq!(east.cross(&north), Point3D::from(Direction3D::Up));
+    }
+
+    #[test]
+    fn test_step() {
+        let mut p = Point3D::new(1, 1, 1);
+
+        assert_eq!(p + Direction3D::Down, Point3D::new(1, 1, 0));
+        assert_eq!(p.step(Direction3D::West, 3), Point3D::new(-2, 1, 1));
+
+        p += Direction3D::North;
+        assert_eq!(p, Point3D::new(1, 2, 1));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let p = Point3D::new(5, -2, 0);
+
+        let six = p.neighbors6();
+        assert!(six.iter().all(|n| n.manhattan_distance(&p) == 1));
+        assert_eq!(six.iter().collect::<HashSet<_>>().len(), 6);
+
+        let all = p.neighbors26();
+        assert_eq!(all[0], Point3D::new(4, -3, -1));
+        assert_eq!(all[25], Point3D::new(6, -1, 1));
+        assert!(!all.contains(&p));
+        assert_eq!(all.iter().collect::<HashSet<_>>().len(), 26);
+        assert!(six.iter().all(|n| all.contains(n)));
+    }
+}
@@ -1,4 +1,7 @@
-use std::ops::Add;
+use std::fmt;
+use std::ops::{Add, AddAssign, Neg};
+
+use crate::points::{Number, Point};
 
 #[derive(Debug, PartialEq, Clone, Copy, Hash, Eq)]
 pub enum Direction {
@@ -12,11 +15,36 @@ pub enum Direction {
     DownRight,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DirectionError {
     InvalidDirection,
+    /// The character doesn't name a direction
+    InvalidChar(char),
 }
 
+impl fmt::Display for DirectionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DirectionError::InvalidDirection => write!(f, "the points do not define a direction"),
+            DirectionError::InvalidChar(c) => write!(f, "{c:?} is not a direction"),
+        }
+    }
+}
+
+impl std::error::Error for DirectionError {}
+
+/// Directions in clockwise order starting from `Up`, giving each its `index`.
+const CLOCKWISE: [Direction; 8] = [
+    Direction::Up,
+    Direction::UpRight,
+    Direction::Right,
+    Direction::DownRight,
+    Direction::Down,
+    Direction::DownLeft,
+    Direction::Left,
+    Direction::UpLeft,
+];
+
 // direction to (x, y)
 impl From<Direction> for (isize, isize) {
     fn from(d: Direction) -> Self {
@@ -57,18 +85,139 @@ impl Add<Direction> for (usize, usize) {
     }
 }
 
-impl From<char> for Direction {
-    fn from(value: char) -> Self {
+// direction to a unit step on a signed point
+impl<T: Number + Neg<Output = T>> From<Direction> for Point<T> {
+    fn from(d: Direction) -> Self {
+        let unit = |delta: isize| match delta {
+            1 => T::ONE,
+            -1 => -T::ONE,
+            _ => T::ZERO,
+        };
+
+        Point::new(unit(d.x_delta()), unit(d.y_delta()))
+    }
+}
+
+// point + direction, for signed points (unsigned ones use `checked_step`)
+impl<T: Number + Neg<Output = T>> Add<Direction> for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, d: Direction) -> Self::Output {
+        self + Point::from(d)
+    }
+}
+
+impl<T: Number + Neg<Output = T>> AddAssign<Direction> for Point<T> {
+    fn add_assign(&mut self, d: Direction) {
+        *self = *self + d;
+    }
+}
+
+/// Integer coordinates that can move by a whole number of steps without overflowing.
+pub trait CheckedStep: Number {
+    /// Moves `n` steps in the direction of `sign` (`-1`, `0` or `1`), or returns `None` if the
+    /// result doesn't fit in the type.
+    fn checked_step(self, sign: isize, n: Self) -> Option<Self>;
+}
+
+macro_rules! impl_checked_step {
+    ($($t:ty),*) => {
+        $(
+            impl CheckedStep for $t {
+                fn checked_step(self, sign: isize, n: Self) -> Option<Self> {
+                    match sign {
+                        1 => self.checked_add(n),
+                        -1 => self.checked_sub(n),
+                        _ => Some(self),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_step!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize
+);
+
+impl<T: Number + Neg<Output = T>> Point<T> {
+    /// Moves `n` steps in the given direction.
+    #[must_use]
+    pub fn step(&self, direction: Direction, n: T) -> Self {
+        *self + Point::from(direction) * n
+    }
+
+    /// Returns the 4 orthogonal neighbors, in the order of `Direction::cardinal`.
+    #[must_use]
+    pub fn neighbors4(&self) -> [Self; 4] {
+        Direction::cardinal().map(|d| *self + d)
+    }
+
+    /// Returns the 8 surrounding neighbors, in the order of `Direction::all`.
+    #[must_use]
+    pub fn neighbors8(&self) -> [Self; 8] {
+        Direction::all().map(|d| *self + d)
+    }
+}
+
+impl<T: CheckedStep> Point<T> {
+    /// Moves `n` steps in the given direction, or returns `None` if a coordinate would leave
+    /// its type's range (e.g. go below zero for unsigned points).
+    #[must_use]
+    pub fn checked_step(&self, direction: Direction, n: T) -> Option<Self> {
+        Some(Point::new(
+            self.x.checked_step(direction.x_delta(), n)?,
+            self.y.checked_step(direction.y_delta(), n)?,
+        ))
+    }
+
+    /// Returns the orthogonal neighbors that fit in the coordinate type.
+    pub fn checked_neighbors4(&self) -> impl Iterator<Item = Self> + '_ {
+        Direction::cardinal()
+            .into_iter()
+            .filter_map(|d| self.checked_step(d, T::ONE))
+    }
+
+    /// Returns the surrounding neighbors that fit in the coordinate type.
+    pub fn checked_neighbors8(&self) -> impl Iterator<Item = Self> + '_ {
+        Direction::all()
+            .into_iter()
+            .filter_map(|d| self.checked_step(d, T::ONE))
+    }
+}
+
+/// Accepts arrows (`^v<>`), `UDLR` and compass points (`NSEW`), in either case.
+impl TryFrom<char> for Direction {
+    type Error = DirectionError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
         match value {
-            '^' => Self::Up,
-            '>' => Self::Right,
-            '<' => Self::Left,
-            'v' => Self::Down,
-            _ => panic!(),
+            '^' | 'U' | 'u' | 'N' | 'n' => Ok(Self::Up),
+            'v' | 'D' | 'd' | 'S' | 's' => Ok(Self::Down),
+            '<' | 'L' | 'l' | 'W' | 'w' => Ok(Self::Left),
+            '>' | 'R' | 'r' | 'E' | 'e' => Ok(Self::Right),
+            _ => Err(DirectionError::InvalidChar(value)),
         }
     }
 }
 
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Direction::Up => "up",
+            Direction::Down => "down",
+            Direction::Left => "left",
+            Direction::Right => "right",
+            Direction::UpLeft => "up-left",
+            Direction::UpRight => "up-right",
+            Direction::DownLeft => "down-left",
+            Direction::DownRight => "down-right",
+        };
+
+        f.write_str(name)
+    }
+}
+
 impl Direction {
     #[must_use]
     pub fn x_delta(&self) -> isize {
@@ -88,27 +237,31 @@ impl Direction {
         }
     }
 
-    /// Creates a direction from two points.
+    /// Creates a direction from two points, given as `Point`s or `(x, y)` tuples.
+    ///
+    /// Points that are not aligned give the diagonal pointing towards `to`.
     ///
     /// # Errors
     ///
     /// Returns `DirectionError::InvalidDirection` if the two points are the same.
-    ///
-    /// # Panics
-    ///
-    /// Panics if the normalized direction delta is not one of the 8 cardinal directions.
-    pub fn from_points(
-        from: (isize, isize),
-        to: (isize, isize),
+    pub fn from_points<T: Number>(
+        from: impl Into<Point<T>>,
+        to: impl Into<Point<T>>,
     ) -> Result<Direction, DirectionError> {
-        let dx = to.0 - from.0;
-        let dy = to.1 - from.1;
+        let (from, to) = (from.into(), to.into());
 
-        // Normalize the deltas to -1, 0, or 1
-        let dx = dx.signum();
-        let dy = dy.signum();
+        // Normalize the deltas to -1, 0, or 1, comparing so unsigned points work too
+        let sign = |from: T, to: T| -> isize {
+            if to > from {
+                1
+            } else if to < from {
+                -1
+            } else {
+                0
+            }
+        };
 
-        match (dx, dy) {
+        match (sign(from.x, to.x), sign(from.y, to.y)) {
             (0, -1) => Ok(Direction::Up),
             (0, 1) => Ok(Direction::Down),
             (-1, 0) => Ok(Direction::Left),
@@ -117,41 +270,68 @@ impl Direction {
             (1, -1) => Ok(Direction::UpRight),
             (-1, 1) => Ok(Direction::DownLeft),
             (1, 1) => Ok(Direction::DownRight),
-            (0, 0) => Err(DirectionError::InvalidDirection),
-            _ => panic!("Invalid direction: ({dx}, {dy})"),
+            _ => Err(DirectionError::InvalidDirection),
         }
     }
 
-    /// Turns the direction 90 degrees clockwise.
-    ///
-    /// # Panics
-    ///
-    /// Panics if called on a diagonal direction (`UpLeft`, `UpRight`, `DownLeft`, `DownRight`).
+    /// Returns the position of the direction in clockwise order, `Up` being 0 and `UpLeft` 7.
     #[must_use]
-    pub fn turn_clockwise(&self) -> Direction {
+    pub fn index(&self) -> usize {
         match self {
-            Direction::Up => Direction::Right,
-            Direction::Right => Direction::Down,
-            Direction::Down => Direction::Left,
-            Direction::Left => Direction::Up,
-            _ => panic!("Invalid direction"),
+            Direction::Up => 0,
+            Direction::UpRight => 1,
+            Direction::Right => 2,
+            Direction::DownRight => 3,
+            Direction::Down => 4,
+            Direction::DownLeft => 5,
+            Direction::Left => 6,
+            Direction::UpLeft => 7,
         }
     }
 
+    /// Returns the direction at `index` in clockwise order, wrapping around every 8.
+    #[must_use]
+    pub fn from_index(index: usize) -> Direction {
+        CLOCKWISE[index % CLOCKWISE.len()]
+    }
+
+    #[must_use]
+    pub fn is_cardinal(&self) -> bool {
+        self.index().is_multiple_of(2)
+    }
+
+    #[must_use]
+    pub fn is_diagonal(&self) -> bool {
+        !self.is_cardinal()
+    }
+
+    #[must_use]
+    pub fn opposite(&self) -> Direction {
+        Self::from_index(self.index() + 4)
+    }
+
+    /// Turns the direction 90 degrees clockwise.
+    #[must_use]
+    pub fn turn_clockwise(&self) -> Direction {
+        Self::from_index(self.index() + 2)
+    }
+
     /// Turns the direction 90 degrees counterclockwise.
-    ///
-    /// # Panics
-    ///
-    /// Panics if called on a diagonal direction (`UpLeft`, `UpRight`, `DownLeft`, `DownRight`).
     #[must_use]
     pub fn turn_counterclockwise(&self) -> Direction {
-        match self {
-            Direction::Up => Direction::Left,
-            Direction::Left => Direction::Down,
-            Direction::Down => Direction::Right,
-            Direction::Right => Direction::Up,
-            _ => panic!("Invalid direction"),
-        }
+        Self::from_index(self.index() + 6)
+    }
+
+    /// Turns the direction 45 degrees clockwise.
+    #[must_use]
+    pub fn turn_clockwise_45(&self) -> Direction {
+        Self::from_index(self.index() + 1)
+    }
+
+    /// Turns the direction 45 degrees counterclockwise.
+    #[must_use]
+    pub fn turn_counterclockwise_45(&self) -> Direction {
+        Self::from_index(self.index() + 7)
     }
 
     #[must_use]
@@ -191,3 +371,147 @@ impl Direction {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_plus_direction() {
+        let mut p = Point::new(3, 3);
+
+        assert_eq!(p + Direction::Up, Point::new(3, 2));
+        assert_eq!(p + Direction::DownLeft, Point::new(2, 4));
+        assert_eq!(p.step(Direction::Right, 5), Point::new(8, 3));
+
+        p += Direction::UpRight;
+        assert_eq!(p, Point::new(4, 2));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let p = Point::new(0i64, 0);
+
+        assert_eq!(
+            p.neighbors4(),
+            [
+                Point::new(0, -1),
+                Point::new(0, 1),
+                Point::new(-1, 0),
+                Point::new(1, 0)
+            ]
+        );
+        assert_eq!(p.neighbors8().len(), 8);
+        assert!(!p.neighbors8().contains(&p));
+    }
+
+    #[test]
+    fn test_checked_step() {
+        let p = Point::new(1usize, 0);
+
+        assert_eq!(p.checked_step(Direction::Up, 1), None);
+        assert_eq!(p.checked_step(Direction::Left, 1), Some(Point::new(0, 0)));
+        assert_eq!(p.checked_step(Direction::Left, 2), None);
+        assert_eq!(
+            p.checked_step(Direction::DownRight, 3),
+            Some(Point::new(4, 3))
+        );
+        assert_eq!(
+            Point::new(u8::MAX, 0).checked_step(Direction::Right, 1),
+            None
+        );
+
+        let corner: Vec<_> = Point::new(0usize, 0).checked_neighbors8().collect();
+        assert_eq!(
+            corner,
+            vec![Point::new(0, 1), Point::new(1, 0), Point::new(1, 1)]
+        );
+        assert_eq!(Point::new(5u32, 5).checked_neighbors4().count(), 4);
+    }
+
+    #[test]
+    fn test_index_round_trip() {
+        for (i, direction) in CLOCKWISE.iter().enumerate() {
+            assert_eq!(direction.index(), i);
+            assert_eq!(Direction::from_index(i), *direction);
+        }
+        assert_eq!(Direction::from_index(10), Direction::Right);
+
+        assert!(Direction::Left.is_cardinal());
+        assert!(Direction::DownLeft.is_diagonal());
+        assert_eq!(
+            Direction::cardinal()
+                .iter()
+                .filter(|d| d.is_cardinal())
+                .count(),
+            4
+        );
+    }
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(Direction::Up.opposite(), Direction::Down);
+        assert_eq!(Direction::UpLeft.opposite(), Direction::DownRight);
+
+        assert_eq!(Direction::Left.turn_clockwise(), Direction::Up);
+        assert_eq!(Direction::UpRight.turn_clockwise(), Direction::DownRight);
+        assert_eq!(
+            Direction::DownLeft.turn_counterclockwise(),
+            Direction::DownRight
+        );
+        assert_eq!(Direction::Up.turn_clockwise_45(), Direction::UpRight);
+        assert_eq!(Direction::Up.turn_counterclockwise_45(), Direction::UpLeft);
+
+        for direction in Direction::all() {
+            assert_eq!(
+                direction.turn_clockwise().turn_counterclockwise(),
+                direction
+            );
+            assert_eq!(
+                direction.turn_clockwise_45().turn_clockwise_45(),
+                direction.turn_clockwise()
+            );
+            assert_eq!(
+                direction.turn_clockwise().turn_clockwise(),
+                direction.opposite()
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_from_char() {
+        for c in ['^', 'U', 'n', 'N'] {
+            assert_eq!(Direction::try_from(c), Ok(Direction::Up));
+        }
+        assert_eq!(Direction::try_from('v'), Ok(Direction::Down));
+        assert_eq!(Direction::try_from('W'), Ok(Direction::Left));
+        assert_eq!(Direction::try_from('r'), Ok(Direction::Right));
+        assert_eq!(
+            Direction::try_from('x'),
+            Err(DirectionError::InvalidChar('x'))
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Direction::Up.to_string(), "up");
+        assert_eq!(Direction::DownLeft.to_string(), "down-left");
+        assert_eq!(
+            DirectionError::InvalidChar('?').to_string(),
+            "'?' is not a direction"
+        );
+    }
+
+    #[test]
+    fn test_from_points() {
+        assert_eq!(
+            Direction::from_points(Point::new(2usize, 5), Point::new(2, 1)).unwrap(),
+            Direction::Up
+        );
+        assert_eq!(
+            Direction::from_points((0, 0), (7, 3)).unwrap(),
+            Direction::DownRight
+        );
+        assert!(Direction::from_points(Point::new(1, 1), Point::new(1, 1)).is_err());
+    }
+}
@@ -0,0 +1,740 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::compression::CoordinateCompression;
+use crate::points::{Number, Point};
+use crate::prefix_sum::PrefixSum2D;
+use crate::rect::Rect;
+use crate::segment::{Segment, Widen};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolygonError {
+    /// A polygon needs at least 3 vertices, holds the number given
+    TooFewVertices(usize),
+    /// The edge starting at this vertex index is neither horizontal nor vertical
+    DiagonalEdge(usize),
+}
+
+impl fmt::Display for PolygonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PolygonError::TooFewVertices(count) => {
+                write!(f, "a polygon needs at least 3 vertices, got {count}")
+            }
+            PolygonError::DiagonalEdge(index) => {
+                write!(f, "edge starting at vertex {index} is not axis-aligned")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolygonError {}
+
+/// Winding direction of a polygon, using the usual math convention (y axis pointing up).
+///
+/// With screen coordinates (y axis pointing down) the two directions appear swapped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    /// Zero signed area (collinear or self-cancelling vertices)
+    Degenerate,
+}
+
+/// Position of a point relative to a polygon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// A simple polygon given by its vertices in order; the last vertex connects back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon<T: Number> {
+    vertices: Vec<Point<T>>,
+}
+
+impl<T: Number> Polygon<T> {
+    #[must_use]
+    pub fn new(vertices: Vec<Point<T>>) -> Self {
+        Polygon { vertices }
+    }
+
+    /// Creates a polygon whose edges are all horizontal or vertical.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PolygonError::TooFewVertices` if fewer than 3 vertices are given, and
+    /// `PolygonError::DiagonalEdge` if any edge is not axis-aligned.
+    pub fn rectilinear(vertices: Vec<Point<T>>) -> Result<Self, PolygonError> {
+        let polygon = Polygon::new(vertices);
+        polygon.validate_rectilinear()?;
+
+        Ok(polygon)
+    }
+
+    #[must_use]
+    pub fn vertices(&self) -> &[Point<T>] {
+        &self.vertices
+    }
+
+    /// Iterates over the edges as `(start, end)` pairs, including the closing edge.
+    pub fn edges(&self) -> impl Iterator<Item = (Point<T>, Point<T>)> + '_ {
+        let n = self.vertices.len();
+
+        (0..n).map(move |i| (self.vertices[i], self.vertices[(i + 1) % n]))
+    }
+
+    /// Checks that the polygon has at least 3 vertices and only axis-aligned edges.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first problem found, see `Polygon::rectilinear`.
+    pub fn validate_rectilinear(&self) -> Result<(), PolygonError> {
+        if self.vertices.len() < 3 {
+            return Err(PolygonError::TooFewVertices(self.vertices.len()));
+        }
+
+        match self.edges().position(|(a, b)| a.x != b.x && a.y != b.y) {
+            Some(index) => Err(PolygonError::DiagonalEdge(index)),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the two halves of the shoelace sum, `Σ x_i * y_(i+1)` and `Σ x_(i+1) * y_i`.
+    ///
+    /// Keeping them apart avoids negative intermediate values for unsigned types.
+    fn shoelace_terms(&self) -> (T, T) {
+        self.edges().fold((T::ZERO, T::ZERO), |(pos, neg), (a, b)| {
+            (pos + a.x * b.y, neg + b.x * a.y)
+        })
+    }
+
+    /// Returns twice the enclosed area, which is always exact for integer coordinates.
+    #[must_use]
+    pub fn twice_area(&self) -> T {
+        let (pos, neg) = self.shoelace_terms();
+
+        if pos > neg { pos - neg } else { neg - pos }
+    }
+
+    /// Returns the enclosed area using the shoelace formula.
+    ///
+    /// For integer types this rounds down, which is exact for rectilinear polygons.
+    #[must_use]
+    pub fn area(&self) -> T {
+        self.twice_area() / (T::ONE + T::ONE)
+    }
+
+    /// Returns the sum of the edge lengths, measured with the Manhattan distance.
+    ///
+    /// This is the exact perimeter of rectilinear polygons.
+    #[must_use]
+    pub fn perimeter(&self) -> T {
+        self.edges()
+            .fold(T::ZERO, |total, (a, b)| total + a.manhattan_distance(&b))
+    }
+
+    #[must_use]
+    pub fn orientation(&self) -> Orientation {
+        let (pos, neg) = self.shoelace_terms();
+
+        match pos.partial_cmp(&neg) {
+            Some(Ordering::Greater) => Orientation::CounterClockwise,
+            Some(Ordering::Less) => Orientation::Clockwise,
+            _ => Orientation::Degenerate,
+        }
+    }
+
+    /// Locates a point relative to the polygon using ray casting.
+    #[must_use]
+    pub fn locate(&self, point: Point<T>) -> Location {
+        self.locate_scaled(point, T::ONE)
+    }
+
+    /// Returns `true` if the point is inside the polygon or on its boundary.
+    #[must_use]
+    pub fn contains(&self, point: Point<T>) -> bool {
+        self.locate(point) != Location::Outside
+    }
+
+    /// Locates `point` relative to the polygon scaled by `scale` around the origin.
+    ///
+    /// Scaling by 2 lets midpoints of integer coordinates be tested exactly.
+    fn locate_scaled(&self, point: Point<T>, scale: T) -> Location {
+        let mut inside = false;
+
+        for (a, b) in self.edges() {
+            let a = Point::new(a.x * scale, a.y * scale);
+            let b = Point::new(b.x * scale, b.y * scale);
+            let side = cross_sign(a, b, point);
+
+            if side == Ordering::Equal && Rect::new(a, b).contains(point) {
+                return Location::Boundary;
+            }
+
+            // The edge straddles the horizontal ray going right from the point
+            if (a.y > point.y) != (b.y > point.y) {
+                let crossing_on_right = if b.y > a.y {
+                    side == Ordering::Greater
+                } else {
+                    side == Ordering::Less
+                };
+
+                if crossing_on_right {
+                    inside = !inside;
+                }
+            }
+        }
+
+        if inside {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+}
+
+impl<T: Widen> Polygon<T> {
+    /// Returns `true` if the rectangle lies entirely inside the polygon, boundary included.
+    ///
+    /// Diagonal edges are checked exactly with segment predicates.
+    #[must_use]
+    pub fn contains_rect(&self, rect: &Rect<T>) -> bool {
+        let (min, max) = (rect.min(), rect.max());
+
+        if min.x == max.x || min.y == max.y {
+            return self.contains_axis_segment(min, max);
+        }
+
+        // With no edge crossing its interior, the rectangle is either fully inside or fully
+        // outside, which its center tells apart
+        if self
+            .edges()
+            .any(|(p, q)| Segment::new(p, q).crosses_interior(rect))
+        {
+            return false;
+        }
+
+        let two = T::ONE + T::ONE;
+        let doubled_center = Point::new(min.x + max.x, min.y + max.y);
+
+        self.locate_scaled(doubled_center, two) != Location::Outside
+    }
+
+    /// Checks a horizontal or vertical segment (or a single point) from `min` to `max`.
+    ///
+    /// Along the segment, the inside/outside status can only change at vertex coordinates, so
+    /// testing those and the midpoints between them is enough.
+    fn contains_axis_segment(&self, min: Point<T>, max: Point<T>) -> bool {
+        let horizontal = min.y == max.y;
+        let along = |p: Point<T>| if horizontal { p.x } else { p.y };
+        let at = |v: T| {
+            if horizontal {
+                Point::new(v, min.y)
+            } else {
+                Point::new(min.x, v)
+            }
+        };
+
+        let (start, end) = (along(min), along(max));
+        let mut events: Vec<T> = self
+            .vertices
+            .iter()
+            .map(|&v| along(v))
+            .filter(|&v| start < v && v < end)
+            .collect();
+        events.push(start);
+        events.push(end);
+        events.sort_unstable();
+        events.dedup();
+
+        let two = T::ONE + T::ONE;
+        events.iter().all(|&v| self.contains(at(v)))
+            && events.windows(2).all(|pair| {
+                let doubled =
+                    Point::new(at(pair[0]).x + at(pair[1]).x, at(pair[0]).y + at(pair[1]).y);
+                self.locate_scaled(doubled, two) != Location::Outside
+            })
+    }
+}
+
+impl<T: Number + Ord> Polygon<T> {
+    /// Rasterizes a rectilinear polygon over its compressed vertex coordinates, so rectangle
+    /// containment can then be answered in `O(log n)`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the polygon is not rectilinear, see `Polygon::rectilinear`.
+    pub fn compress(&self) -> Result<CompressedPolygon<T>, PolygonError> {
+        self.validate_rectilinear()?;
+
+        Ok(CompressedPolygon::new(self))
+    }
+}
+
+/// A rectilinear polygon rasterized over its compressed vertex coordinates.
+///
+/// Along each axis, the compressed values `v_0 < v_1 < ...` split the line into elements:
+/// element `2i` is the value `v_i` itself and element `2i + 1` the open gap between `v_i` and
+/// `v_(i+1)`. Every 2D element (a vertex, an edge piece or an open cell) is either entirely
+/// inside or entirely outside the polygon, so a rectangle is contained exactly when none of the
+/// elements it covers is outside, which a prefix sum counts in constant time.
+#[derive(Debug, Clone)]
+pub struct CompressedPolygon<T: Number + Ord> {
+    vertices: Vec<Point<T>>,
+    xs: CoordinateCompression<T>,
+    ys: CoordinateCompression<T>,
+    /// Number of elements outside the polygon, indexed by `(y element, x element)`
+    outside: PrefixSum2D<u32>,
+}
+
+impl<T: Number + Ord> CompressedPolygon<T> {
+    fn new(polygon: &Polygon<T>) -> Self {
+        let xs = CoordinateCompression::new(polygon.vertices.iter().map(|v| v.x));
+        let ys = CoordinateCompression::new(polygon.vertices.iter().map(|v| v.y));
+
+        // Elements plus a one-element margin on every side, so the outside is connected
+        let width = 2 * xs.len() + 1;
+        let height = 2 * ys.len() + 1;
+        let mut boundary = vec![false; width * height];
+
+        for (a, b) in polygon.edges() {
+            let (Some(ax), Some(bx), Some(ay), Some(by)) =
+                (xs.index(a.x), xs.index(b.x), ys.index(a.y), ys.index(b.y))
+            else {
+                continue;
+            };
+
+            for row in 2 * ay.min(by)..=2 * ay.max(by) {
+                for col in 2 * ax.min(bx)..=2 * ax.max(bx) {
+                    boundary[(row + 1) * width + col + 1] = true;
+                }
+            }
+        }
+
+        // Flood fill the outside from the margin
+        let mut outside = vec![false; width * height];
+        let mut stack = vec![0];
+        outside[0] = true;
+
+        while let Some(cell) = stack.pop() {
+            let (row, col) = (cell / width, cell % width);
+            let neighbors = [
+                (row > 0).then(|| cell - width),
+                (row + 1 < height).then(|| cell + width),
+                (col > 0).then(|| cell - 1),
+                (col + 1 < width).then(|| cell + 1),
+            ];
+
+            for next in neighbors.into_iter().flatten() {
+                if !boundary[next] && !outside[next] {
+                    outside[next] = true;
+                    stack.push(next);
+                }
+            }
+        }
+
+        let outside = PrefixSum2D::from_fn(height - 2, width - 2, |row, col| {
+            u32::from(outside[(row + 1) * width + col + 1])
+        });
+
+        CompressedPolygon {
+            vertices: polygon.vertices.clone(),
+            xs,
+            ys,
+            outside,
+        }
+    }
+
+    /// Returns the element holding `value` along an axis, or `None` outside the vertex range.
+    fn element(axis: &CoordinateCompression<T>, value: T) -> Option<usize> {
+        match axis.index(value) {
+            Some(index) => Some(2 * index),
+            None => {
+                let rank = axis.rank(value);
+                (rank > 0 && rank < axis.len()).then(|| 2 * rank - 1)
+            }
+        }
+    }
+
+    /// Returns `true` if the point is inside the polygon or on its boundary.
+    #[must_use]
+    pub fn contains(&self, point: Point<T>) -> bool {
+        self.contains_rect(&Rect::new(point, point))
+    }
+
+    /// Returns `true` if the rectangle lies entirely inside the polygon, boundary included.
+    #[must_use]
+    pub fn contains_rect(&self, rect: &Rect<T>) -> bool {
+        let (min, max) = (rect.min(), rect.max());
+        let (Some(left), Some(right), Some(top), Some(bottom)) = (
+            Self::element(&self.xs, min.x),
+            Self::element(&self.xs, max.x),
+            Self::element(&self.ys, min.y),
+            Self::element(&self.ys, max.y),
+        ) else {
+            return false;
+        };
+
+        self.outside.sum(top, left, bottom, right) == 0
+    }
+
+    /// Finds the largest rectangle lying inside the polygon whose opposite corners are two
+    /// of its vertices.
+    ///
+    /// Rectangles are compared by `Rect::inclusive_area`. Returns `None` if there are fewer than
+    /// two vertices.
+    #[must_use]
+    pub fn largest_vertex_rect(&self) -> Option<Rect<T>> {
+        let mut best: Option<(Rect<T>, T)> = None;
+
+        for (i, &a) in self.vertices.iter().enumerate() {
+            for &b in &self.vertices[i + 1..] {
+                let rect = Rect::new(a, b);
+                let area = rect.inclusive_area();
+
+                // The containment check is the expensive part, skip it when it can't help
+                if best.is_none_or(|(_, best_area)| area > best_area) && self.contains_rect(&rect) {
+                    best = Some((rect, area));
+                }
+            }
+        }
+
+        best.map(|(rect, _)| rect)
+    }
+}
+
+/// Finds the largest rectangle, by `Rect::inclusive_area`, whose opposite corners are two of
+/// the points.
+///
+/// Only points on the four "staircases" (points not dominated towards a corner by another
+/// point) can be part of the best rectangle, so the pairwise search is limited to those.
+/// Returns `None` if there are fewer than two points.
+#[must_use]
+pub fn largest_rect_from_points<T: Number>(points: &[Point<T>]) -> Option<Rect<T>> {
+    if points.len() < 2 {
+        return None;
+    }
+
+    let by = |x_ascending: bool, y_ascending: bool| {
+        move |a: &Point<T>, b: &Point<T>| {
+            let x = a.x.partial_cmp(&b.x).unwrap_or(Ordering::Equal);
+            let y = a.y.partial_cmp(&b.y).unwrap_or(Ordering::Equal);
+            let x = if x_ascending { x } else { x.reverse() };
+            let y = if y_ascending { y } else { y.reverse() };
+            x.then(y)
+        }
+    };
+
+    // Keeps the points whose y beats every point seen before them in the sorted order
+    let staircase = |x_ascending: bool, y_ascending: bool| {
+        let mut sorted = points.to_vec();
+        sorted.sort_by(by(x_ascending, y_ascending));
+
+        let mut kept: Vec<Point<T>> = Vec::new();
+        for point in sorted {
+            let beats = kept.last().is_none_or(|last| {
+                if y_ascending {
+                    point.y < last.y
+                } else {
+                    point.y > last.y
+                }
+            });
+            if beats {
+                kept.push(point);
+            }
+        }
+        kept
+    };
+
+    let top_left = staircase(true, true);
+    let bottom_right = staircase(false, false);
+    let bottom_left = staircase(true, false);
+    let top_right = staircase(false, true);
+
+    let mut best: Option<(Rect<T>, T)> = None;
+    for (corners, opposites) in [(&top_left, &bottom_right), (&bottom_left, &top_right)] {
+        for &a in corners {
+            for &b in opposites {
+                let rect = Rect::new(a, b);
+                let area = rect.inclusive_area();
+                if a != b && best.is_none_or(|(_, best_area)| area > best_area) {
+                    best = Some((rect, area));
+                }
+            }
+        }
+    }
+
+    // Every point can be on a single staircase only when they all coincide
+    best.map(|(rect, _)| rect)
+        .or(Some(Rect::new(points[0], points[1])))
+}
+
+/// Finds the largest all-`true` rectangle in a grid, using the histogram stack method.
+///
+/// Returns the rectangle of cells (inclusive, `x` being the column), or `None` if the grid has
+/// no `true` cell.
+#[must_use]
+pub fn largest_rect_in_grid(grid: &[Vec<bool>]) -> Option<Rect<usize>> {
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+    let mut heights = vec![0usize; width];
+    let mut best: Option<(Rect<usize>, usize)> = None;
+
+    for (row, cells) in grid.iter().enumerate() {
+        // Height of the run of `true` cells ending at this row, per column
+        for (col, height) in heights.iter_mut().enumerate() {
+            *height = if cells.get(col).copied().unwrap_or(false) {
+                *height + 1
+            } else {
+                0
+            };
+        }
+
+        // Largest rectangle under the histogram, with a stack of increasing heights
+        let mut stack: Vec<usize> = Vec::new();
+        for col in 0..=width {
+            let current = heights.get(col).copied().unwrap_or(0);
+
+            while let Some(&top) = stack.last() {
+                if heights[top] < current {
+                    break;
+                }
+                stack.pop();
+
+                let height = heights[top];
+                let left = stack.last().map_or(0, |&i| i + 1);
+                let area = height * (col - left);
+
+                if area > 0 && best.is_none_or(|(_, best_area)| area > best_area) {
+                    let rect =
+                        Rect::new(Point::new(left, row + 1 - height), Point::new(col - 1, row));
+                    best = Some((rect, area));
+                }
+            }
+
+            stack.push(col);
+        }
+    }
+
+    best.map(|(rect, _)| rect)
+}
+
+impl<T: Number> From<Vec<Point<T>>> for Polygon<T> {
+    fn from(vertices: Vec<Point<T>>) -> Self {
+        Polygon::new(vertices)
+    }
+}
+
+/// Returns the sign of the cross product `(b - a) × (p - a)`.
+///
+/// `Greater` means `p` is to the left of the line from `a` to `b` (y axis pointing up).
+/// The product is expanded so that only sums are compared, which keeps unsigned types from
+/// underflowing.
+fn cross_sign<T: Number>(a: Point<T>, b: Point<T>, p: Point<T>) -> Ordering {
+    let positive = b.x * p.y + b.y * a.x + a.y * p.x;
+    let negative = b.x * a.y + a.x * p.y + b.y * p.x;
+
+    positive.partial_cmp(&negative).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The example polygon from 2025 day 9
+    fn example() -> Polygon<i64> {
+        Polygon::rectilinear(
+            [
+                (7, 1),
+                (11, 1),
+                (11, 7),
+                (9, 7),
+                (9, 5),
+                (2, 5),
+                (2, 3),
+                (7, 3),
+            ]
+            .into_iter()
+            .map(Point::from)
+            .collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_area_and_perimeter() {
+        let square = Polygon::new(vec![
+            Point::new(0, 0),
+            Point::new(4, 0),
+            Point::new(4, 4),
+            Point::new(0, 4),
+        ]);
+        assert_eq!(square.area(), 16);
+        assert_eq!(square.perimeter(), 16);
+
+        let polygon = example();
+        assert_eq!(polygon.area(), 30);
+        assert_eq!(polygon.perimeter(), 30);
+
+        // Unsigned coordinates must not underflow
+        let unsigned = Polygon::new(vec![
+            Point::new(0u32, 0),
+            Point::new(3, 0),
+            Point::new(3, 2),
+            Point::new(0, 2),
+        ]);
+        assert_eq!(unsigned.area(), 6);
+    }
+
+    #[test]
+    fn test_orientation() {
+        let ccw = Polygon::new(vec![Point::new(0, 0), Point::new(2, 0), Point::new(0, 2)]);
+        assert_eq!(ccw.orientation(), Orientation::CounterClockwise);
+
+        let cw = Polygon::new(vec![Point::new(0, 0), Point::new(0, 2), Point::new(2, 0)]);
+        assert_eq!(cw.orientation(), Orientation::Clockwise);
+
+        let flat = Polygon::new(vec![Point::new(0, 0), Point::new(1, 1), Point::new(2, 2)]);
+        assert_eq!(flat.orientation(), Orientation::Degenerate);
+    }
+
+    #[test]
+    fn test_locate() {
+        let polygon = example();
+
+        assert_eq!(polygon.locate(Point::new(8, 4)), Location::Inside);
+        assert_eq!(polygon.locate(Point::new(11, 4)), Location::Boundary);
+        assert_eq!(polygon.locate(Point::new(7, 1)), Location::Boundary);
+        assert_eq!(polygon.locate(Point::new(3, 2)), Location::Outside);
+        assert_eq!(polygon.locate(Point::new(5, 6)), Location::Outside);
+
+        let triangle = Polygon::new(vec![Point::new(0, 0), Point::new(4, 0), Point::new(0, 4)]);
+        assert_eq!(triangle.locate(Point::new(1, 1)), Location::Inside);
+        assert_eq!(triangle.locate(Point::new(2, 2)), Location::Boundary);
+        assert_eq!(triangle.locate(Point::new(3, 3)), Location::Outside);
+    }
+
+    #[test]
+    fn test_validate_rectilinear() {
+        let result = Polygon::rectilinear(vec![Point::new(0, 0), Point::new(1, 0)]);
+        assert_eq!(result, Err(PolygonError::TooFewVertices(2)));
+
+        let result = Polygon::rectilinear(vec![
+            Point::new(0, 0),
+            Point::new(2, 0),
+            Point::new(2, 2),
+            Point::new(1, 3),
+        ]);
+        assert_eq!(result, Err(PolygonError::DiagonalEdge(2)));
+    }
+
+    #[test]
+    fn test_contains_rect() {
+        let polygon = example();
+
+        assert!(polygon.contains_rect(&Rect::new(Point::new(9, 5), Point::new(2, 3))));
+        assert!(polygon.contains_rect(&Rect::new(Point::new(11, 1), Point::new(9, 7))));
+        assert!(!polygon.contains_rect(&Rect::new(Point::new(2, 5), Point::new(11, 1))));
+        assert!(!polygon.contains_rect(&Rect::new(Point::new(2, 3), Point::new(7, 1))));
+
+        // Degenerate rectangles are segments
+        assert!(polygon.contains_rect(&Rect::new(Point::new(2, 4), Point::new(11, 4))));
+        assert!(!polygon.contains_rect(&Rect::new(Point::new(2, 6), Point::new(9, 6))));
+    }
+
+    #[test]
+    fn test_compressed_matches_polygon() {
+        let polygon = example();
+        let compressed = polygon.compress().unwrap();
+
+        for x1 in 0..=12 {
+            for y1 in 0..=8 {
+                let a = Point::new(x1, y1);
+                assert_eq!(compressed.contains(a), polygon.contains(a), "{a}");
+
+                for x2 in x1..=12 {
+                    for y2 in 0..=8 {
+                        let b = Point::new(x2, y2);
+                        assert_eq!(
+                            compressed.contains_rect(&Rect::new(a, b)),
+                            polygon.contains_rect(&Rect::new(a, b)),
+                            "{a} {b}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_largest_vertex_rect() {
+        let compressed = example().compress().unwrap();
+
+        let rect = compressed.largest_vertex_rect().unwrap();
+
+        assert_eq!(rect.inclusive_area(), 24);
+        assert!(compressed.contains_rect(&rect));
+    }
+
+    #[test]
+    fn test_largest_rect_from_points() {
+        let points = example().vertices().to_vec();
+
+        let rect = largest_rect_from_points(&points).unwrap();
+        assert_eq!(rect.inclusive_area(), 50);
+
+        // Anti-diagonal corners
+        let points = [Point::new(0, 10), Point::new(10, 0), Point::new(5, 5)];
+        let rect = largest_rect_from_points(&points).unwrap();
+        assert_eq!(rect, Rect::new(Point::new(0, 0), Point::new(10, 10)));
+        assert_eq!(rect.inclusive_area(), 121);
+
+        assert_eq!(largest_rect_from_points(&[Point::new(1, 1)]), None);
+    }
+
+    #[test]
+    fn test_largest_rect_in_grid() {
+        let grid: Vec<Vec<bool>> = ["#..##", "#####", ".####", "##.#."]
+            .iter()
+            .map(|line| line.chars().map(|c| c == '#').collect())
+            .collect();
+
+        let rect = largest_rect_in_grid(&grid).unwrap();
+        assert_eq!(rect, Rect::new(Point::new(1, 1), Point::new(4, 2)));
+
+        assert_eq!(largest_rect_in_grid(&[vec![false; 3]]), None);
+    }
+
+    #[test]
+    fn test_contains_rect_rejects_notch() {
+        // A U shape whose notch is exactly the rectangle (2, 2)-(4, 6)
+        let polygon = Polygon::rectilinear(vec![
+            Point::new(0, 0),
+            Point::new(6, 0),
+            Point::new(6, 6),
+            Point::new(4, 6),
+            Point::new(4, 2),
+            Point::new(2, 2),
+            Point::new(2, 6),
+            Point::new(0, 6),
+        ])
+        .unwrap();
+
+        assert!(!polygon.contains_rect(&Rect::new(Point::new(2, 2), Point::new(4, 6))));
+        assert!(polygon.contains_rect(&Rect::new(Point::new(0, 0), Point::new(6, 2))));
+        assert!(!polygon.contains_rect(&Rect::new(Point::new(0, 0), Point::new(6, 6))));
+    }
+
+    #[test]
+    fn test_contains_rect_with_diagonal_edge() {
+        let triangle = Polygon::new(vec![Point::new(0, 0), Point::new(8, 0), Point::new(0, 8)]);
+
+        // The hypotenuse passes the rectangle's bounding box without entering it
+        assert!(triangle.contains_rect(&Rect::new(Point::new(0, 0), Point::new(3, 3))));
+        assert!(triangle.contains_rect(&Rect::new(Point::new(0, 0), Point::new(4, 4))));
+        assert!(!triangle.contains_rect(&Rect::new(Point::new(0, 0), Point::new(5, 4))));
+        assert!(!triangle.contains_rect(&Rect::new(Point::new(5, 5), Point::new(7, 7))));
+    }
+}
@@ -0,0 +1,529 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+use std::str::FromStr;
+
+use crate::points::Number;
+
+/// The 6 neighbors of a hexagon, named for a flat-topped grid and listed clockwise from `North`.
+///
+/// A pointy-topped grid is the same grid rotated by 30 degrees: its `e`, `se`, `sw`, `w`, `nw`
+/// and `ne` neighbors are `SouthEast`, `South`, `SouthWest`, `NorthWest`, `North` and
+/// `NorthEast` (see `HexLayout`).
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum HexDirection {
+    North,
+    NorthEast,
+    SouthEast,
+    South,
+    SouthWest,
+    NorthWest,
+}
+
+/// How direction names map onto the grid when parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexLayout {
+    /// Flat top and bottom edges, neighbors `n`, `ne`, `se`, `s`, `sw`, `nw`
+    FlatTop,
+    /// Pointy top and bottom corners, neighbors `e`, `se`, `sw`, `w`, `nw`, `ne`
+    PointyTop,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HexError {
+    /// The text doesn't name a direction of the layout
+    InvalidDirection(String),
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HexError::InvalidDirection(name) => write!(f, "{name:?} is not a hex direction"),
+        }
+    }
+}
+
+impl std::error::Error for HexError {}
+
+const CLOCKWISE: [HexDirection; 6] = [
+    HexDirection::North,
+    HexDirection::NorthEast,
+    HexDirection::SouthEast,
+    HexDirection::South,
+    HexDirection::SouthWest,
+    HexDirection::NorthWest,
+];
+
+impl HexDirection {
+    /// Returns the 6 directions, clockwise from `North`.
+    #[must_use]
+    pub fn all() -> [HexDirection; 6] {
+        CLOCKWISE
+    }
+
+    /// Returns the position of the direction in clockwise order, `North` being 0.
+    #[must_use]
+    pub fn index(&self) -> usize {
+        match self {
+            HexDirection::North => 0,
+            HexDirection::NorthEast => 1,
+            HexDirection::SouthEast => 2,
+            HexDirection::South => 3,
+            HexDirection::SouthWest => 4,
+            HexDirection::NorthWest => 5,
+        }
+    }
+
+    /// Returns the direction at `index` in clockwise order, wrapping around every 6.
+    #[must_use]
+    pub fn from_index(index: usize) -> HexDirection {
+        CLOCKWISE[index % CLOCKWISE.len()]
+    }
+
+    #[must_use]
+    pub fn opposite(&self) -> HexDirection {
+        Self::from_index(self.index() + 3)
+    }
+
+    /// Turns the direction 60 degrees clockwise.
+    #[must_use]
+    pub fn turn_clockwise(&self) -> HexDirection {
+        Self::from_index(self.index() + 1)
+    }
+
+    /// Turns the direction 60 degrees counterclockwise.
+    #[must_use]
+    pub fn turn_counterclockwise(&self) -> HexDirection {
+        Self::from_index(self.index() + 5)
+    }
+
+    /// Parses a direction name of the given layout, in either case.
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexError::InvalidDirection` if the name is not one of the layout's 6 neighbors.
+    pub fn parse(name: &str, layout: HexLayout) -> Result<HexDirection, HexError> {
+        let direction = match (layout, name.to_ascii_lowercase().as_str()) {
+            (HexLayout::FlatTop, "n") | (HexLayout::PointyTop, "nw") => HexDirection::North,
+            (_, "ne") => HexDirection::NorthEast,
+            (HexLayout::FlatTop, "se") | (HexLayout::PointyTop, "e") => HexDirection::SouthEast,
+            (HexLayout::FlatTop, "s") | (HexLayout::PointyTop, "se") => HexDirection::South,
+            (_, "sw") => HexDirection::SouthWest,
+            (HexLayout::FlatTop, "nw") | (HexLayout::PointyTop, "w") => HexDirection::NorthWest,
+            _ => return Err(HexError::InvalidDirection(name.to_string())),
+        };
+
+        Ok(direction)
+    }
+
+    /// Parses a path of directions, either separated by commas and/or whitespace
+    /// (`ne,ne,s`) or written back to back (`esenee`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `HexError::InvalidDirection` with the first name that can't be parsed.
+    pub fn parse_path(path: &str, layout: HexLayout) -> Result<Vec<HexDirection>, HexError> {
+        let mut directions = Vec::new();
+
+        for token in path
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|token| !token.is_empty())
+        {
+            let mut rest = token;
+            while !rest.is_empty() {
+                // Prefer two-letter names, so `se` isn't read as `s` then `e`
+                let two = rest
+                    .get(..2)
+                    .and_then(|name| Self::parse(name, layout).ok());
+                let (direction, len) = match two {
+                    Some(direction) => (direction, 2),
+                    None => {
+                        let name = rest.get(..1).unwrap_or(rest);
+                        (Self::parse(name, layout)?, 1)
+                    }
+                };
+
+                directions.push(direction);
+                rest = &rest[len..];
+            }
+        }
+
+        Ok(directions)
+    }
+
+    /// Returns the axial `(q, r)` step of the direction.
+    #[must_use]
+    pub fn delta<T: Number + Neg<Output = T>>(&self) -> HexCoord<T> {
+        let (zero, one) = (T::ZERO, T::ONE);
+
+        match self {
+            HexDirection::North => HexCoord::new(zero, -one),
+            HexDirection::NorthEast => HexCoord::new(one, -one),
+            HexDirection::SouthEast => HexCoord::new(one, zero),
+            HexDirection::South => HexCoord::new(zero, one),
+            HexDirection::SouthWest => HexCoord::new(-one, one),
+            HexDirection::NorthWest => HexCoord::new(-one, zero),
+        }
+    }
+}
+
+/// Parses flat-top names (`n`, `ne`, `se`, `s`, `sw`, `nw`).
+impl FromStr for HexDirection {
+    type Err = HexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s.trim(), HexLayout::FlatTop)
+    }
+}
+
+impl fmt::Display for HexDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            HexDirection::North => "n",
+            HexDirection::NorthEast => "ne",
+            HexDirection::SouthEast => "se",
+            HexDirection::South => "s",
+            HexDirection::SouthWest => "sw",
+            HexDirection::NorthWest => "nw",
+        };
+
+        f.write_str(name)
+    }
+}
+
+/// Hexagon in axial coordinates `(q, r)`; the third cube coordinate is `s = -q - r`.
+///
+/// `q` grows towards `SouthEast` and `r` towards `South`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct HexCoord<T: Number> {
+    pub q: T,
+    pub r: T,
+}
+
+impl<T: Number> HexCoord<T> {
+    pub fn new(q: T, r: T) -> Self {
+        HexCoord { q, r }
+    }
+
+    pub fn origin() -> Self {
+        HexCoord::new(T::ZERO, T::ZERO)
+    }
+}
+
+impl<T: Number + Neg<Output = T>> HexCoord<T> {
+    /// Creates a hexagon from cube coordinates, which must sum to zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `q + r + s` is not zero.
+    pub fn from_cube(q: T, r: T, s: T) -> Self {
+        assert!(
+            q + r + s == T::ZERO,
+            "cube coordinates ({q}, {r}, {s}) must sum to zero"
+        );
+
+        HexCoord::new(q, r)
+    }
+
+    #[must_use]
+    pub fn s(&self) -> T {
+        -self.q - self.r
+    }
+
+    /// Returns the cube coordinates `(q, r, s)`.
+    #[must_use]
+    pub fn cube(&self) -> (T, T, T) {
+        (self.q, self.r, self.s())
+    }
+
+    #[must_use]
+    pub fn neighbor(&self, direction: HexDirection) -> Self {
+        *self + direction.delta()
+    }
+
+    /// Returns the 6 neighbors, in the order of `HexDirection::all`.
+    #[must_use]
+    pub fn neighbors(&self) -> [Self; 6] {
+        HexDirection::all().map(|d| self.neighbor(d))
+    }
+
+    /// Returns the number of steps from the origin.
+    #[must_use]
+    pub fn length(&self) -> T {
+        let abs = |v: T| if v < T::ZERO { -v } else { v };
+        let (q, r, s) = self.cube();
+        let (q, r, s) = (abs(q), abs(r), abs(s));
+
+        if q >= r && q >= s {
+            q
+        } else if r >= s {
+            r
+        } else {
+            s
+        }
+    }
+
+    /// Returns the number of steps between the two hexagons.
+    #[must_use]
+    pub fn distance(&self, other: &Self) -> T {
+        (*self - *other).length()
+    }
+
+    /// Rotates 60 degrees clockwise around the origin.
+    #[must_use]
+    pub fn rotate_clockwise(&self) -> Self {
+        let (q, r, s) = self.cube();
+
+        HexCoord::from_cube(-r, -s, -q)
+    }
+
+    /// Rotates 60 degrees counterclockwise around the origin.
+    #[must_use]
+    pub fn rotate_counterclockwise(&self) -> Self {
+        let (q, r, s) = self.cube();
+
+        HexCoord::from_cube(-s, -q, -r)
+    }
+
+    /// Rotates 60 degrees clockwise around `pivot`.
+    #[must_use]
+    pub fn rotate_clockwise_around(&self, pivot: &Self) -> Self {
+        (*self - *pivot).rotate_clockwise() + *pivot
+    }
+
+    /// Rotates 60 degrees counterclockwise around `pivot`.
+    #[must_use]
+    pub fn rotate_counterclockwise_around(&self, pivot: &Self) -> Self {
+        (*self - *pivot).rotate_counterclockwise() + *pivot
+    }
+
+    /// Returns the hexagons exactly `radius` steps away, clockwise from the northern corner.
+    ///
+    /// A ring of radius 0 is the hexagon itself.
+    #[must_use]
+    pub fn ring(&self, radius: T) -> Vec<Self> {
+        if radius <= T::ZERO {
+            return vec![*self];
+        }
+
+        let mut ring = Vec::new();
+        let mut current = *self + HexDirection::North.delta() * radius;
+
+        // Each side runs 120 degrees clockwise from the corner it starts at
+        for side in 2..8 {
+            let direction = HexDirection::from_index(side);
+            let mut step = T::ZERO;
+            while step < radius {
+                ring.push(current);
+                current = current.neighbor(direction);
+                step = step + T::ONE;
+            }
+        }
+
+        ring
+    }
+
+    /// Returns the hexagons at most `radius` steps away, ring by ring from the center.
+    #[must_use]
+    pub fn spiral(&self, radius: T) -> Vec<Self> {
+        let mut spiral = vec![*self];
+        let mut current = T::ONE;
+
+        while current <= radius {
+            spiral.extend(self.ring(current));
+            current = current + T::ONE;
+        }
+
+        spiral
+    }
+}
+
+impl<T: Number> Add for HexCoord<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        HexCoord::new(self.q + other.q, self.r + other.r)
+    }
+}
+
+impl<T: Number> Sub for HexCoord<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        HexCoord::new(self.q - other.q, self.r - other.r)
+    }
+}
+
+impl<T: Number> Mul<T> for HexCoord<T> {
+    type Output = Self;
+
+    fn mul(self, scalar: T) -> Self::Output {
+        HexCoord::new(self.q * scalar, self.r * scalar)
+    }
+}
+
+impl<T: Number + Neg<Output = T>> Add<HexDirection> for HexCoord<T> {
+    type Output = Self;
+
+    fn add(self, direction: HexDirection) -> Self::Output {
+        self + direction.delta()
+    }
+}
+
+impl<T: Number + Neg<Output = T>> AddAssign<HexDirection> for HexCoord<T> {
+    fn add_assign(&mut self, direction: HexDirection) {
+        *self = *self + direction;
+    }
+}
+
+impl<T: Number> fmt::Display for HexCoord<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.q, self.r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directions() {
+        for (i, direction) in HexDirection::all().iter().enumerate() {
+            assert_eq!(direction.index(), i);
+            assert_eq!(HexDirection::from_index(i + 6), *direction);
+            assert_eq!(direction.opposite().opposite(), *direction);
+            assert_eq!(
+                direction.turn_clockwise().turn_counterclockwise(),
+                *direction
+            );
+
+            // Opposite steps cancel out
+            assert_eq!(
+                direction.delta::<i32>() + direction.opposite().delta(),
+                HexCoord::origin()
+            );
+        }
+
+        assert_eq!(HexDirection::North.opposite(), HexDirection::South);
+        assert_eq!(
+            HexDirection::SouthWest.turn_clockwise(),
+            HexDirection::NorthWest
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("ne".parse(), Ok(HexDirection::NorthEast));
+        assert_eq!(" S ".parse(), Ok(HexDirection::South));
+        assert_eq!(
+            HexDirection::parse("e", HexLayout::PointyTop),
+            Ok(HexDirection::SouthEast)
+        );
+        assert_eq!(
+            "e".parse::<HexDirection>(),
+            Err(HexError::InvalidDirection("e".to_string()))
+        );
+        assert_eq!(HexDirection::SouthWest.to_string(), "sw");
+    }
+
+    #[test]
+    fn test_parse_path() {
+        let path = HexDirection::parse_path("ne,ne,s,s", HexLayout::FlatTop).unwrap();
+        assert_eq!(path.len(), 4);
+        assert_eq!(
+            path.iter().fold(HexCoord::origin(), |p, &d| p + d),
+            HexCoord::new(2, 0)
+        );
+
+        // Back to back pointy-top names
+        let path = HexDirection::parse_path("nwwswee", HexLayout::PointyTop).unwrap();
+        assert_eq!(
+            path,
+            vec![
+                HexDirection::North,
+                HexDirection::NorthWest,
+                HexDirection::SouthWest,
+                HexDirection::SouthEast,
+                HexDirection::SouthEast
+            ]
+        );
+        assert_eq!(
+            path.iter().fold(HexCoord::origin(), |p, &d| p + d),
+            HexCoord::new(0, 0)
+        );
+
+        assert_eq!(
+            HexDirection::parse_path("n,x", HexLayout::FlatTop),
+            Err(HexError::InvalidDirection("x".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_distance() {
+        let origin = HexCoord::<i64>::origin();
+
+        // 2017 day 11 examples
+        for (path, distance) in [
+            ("ne,ne,ne", 3),
+            ("ne,ne,sw,sw", 0),
+            ("ne,ne,s,s", 2),
+            ("se,sw,se,sw,sw", 3),
+        ] {
+            let end = HexDirection::parse_path(path, HexLayout::FlatTop)
+                .unwrap()
+                .into_iter()
+                .fold(origin, |p, d| p + d);
+            assert_eq!(end.distance(&origin), distance, "{path}");
+        }
+
+        assert_eq!(HexCoord::from_cube(2, -5, 3).length(), 5);
+    }
+
+    #[test]
+    fn test_rotations() {
+        let p = HexCoord::new(2, -1);
+
+        let mut current = p;
+        for _ in 0..6 {
+            current = current.rotate_clockwise();
+            assert_eq!(current.length(), p.length());
+        }
+        assert_eq!(current, p);
+
+        assert_eq!(
+            HexDirection::North.delta::<i32>().rotate_clockwise(),
+            HexDirection::NorthEast.delta()
+        );
+        assert_eq!(p.rotate_clockwise().rotate_counterclockwise(), p);
+
+        let pivot = HexCoord::new(1, 1);
+        assert_eq!(
+            pivot
+                .neighbor(HexDirection::South)
+                .rotate_clockwise_around(&pivot),
+            pivot.neighbor(HexDirection::SouthWest)
+        );
+    }
+
+    #[test]
+    fn test_ring_and_spiral() {
+        let center = HexCoord::new(3, -2);
+
+        assert_eq!(center.ring(0), vec![center]);
+
+        let ring = center.ring(2);
+        assert_eq!(ring.len(), 12);
+        assert!(ring.iter().all(|p| p.distance(&center) == 2));
+        assert_eq!(ring[0], center + HexDirection::North.delta() * 2);
+
+        let spiral = center.spiral(3);
+        assert_eq!(spiral.len(), 37);
+        assert_eq!(spiral[0], center);
+        assert_eq!(
+            spiral
+                .iter()
+                .collect::<std::collections::HashSet<_>>()
+                .len(),
+            37
+        );
+    }
+}
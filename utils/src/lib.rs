@@ -1,10 +1,38 @@
+pub mod beam;
+pub mod bigint;
+pub mod columns;
+pub mod compression;
+pub mod direction3d;
 pub mod directions;
+pub mod geometry;
+pub mod hex;
+pub mod memo;
+pub mod parse;
 pub mod point3d;
+pub mod point_n;
 pub mod points;
-
+pub mod prefix_sum;
+pub mod rect;
+pub mod segment;
+pub mod voxel;
+
+pub use beam::*;
+pub use bigint::*;
+pub use columns::*;
+pub use compression::*;
+pub use direction3d::*;
 pub use directions::*;
+pub use geometry::*;
+pub use hex::*;
+pub use memo::*;
+pub use parse::*;
+pub use point_n::*;
 pub use point3d::*;
 pub use points::*;
+pub use prefix_sum::*;
+pub use rect::*;
+pub use segment::*;
+pub use voxel::*;
 
 pub trait Solution {
     type Input;
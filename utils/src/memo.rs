@@ -0,0 +1,173 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+/// Cache of computed values for memoized recursion.
+///
+/// `get_or_compute` hands the memo back to the computation so it can recurse through it, while
+/// `get_or_compute_iterative` evaluates the same kind of recurrence with an explicit stack, for
+/// inputs deep enough to overflow the call stack.
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Memo {
+            cache: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Memo<K, V> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the cached value for `key`, if it was computed.
+    #[must_use]
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// Number of cached values.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Returns the cached value for `key`, or computes and caches it.
+    ///
+    /// `compute` gets the memo back, so it can call the memoized function recursively, as in
+    /// `memo.get_or_compute(n, |memo| fib(n - 1, memo) + fib(n - 2, memo))`.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            return value.clone();
+        }
+
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    /// Returns the cached value for `key`, or computes it without recursion.
+    ///
+    /// The value of a key is `combine(key, values)`, `values` being those of
+    /// `dependencies(key)` in the same order. Dependencies are evaluated depth first with an
+    /// explicit stack, each of them once.
+    ///
+    /// Returns `None` if the dependencies loop back to a key still being computed.
+    pub fn get_or_compute_iterative<D, C>(
+        &mut self,
+        key: K,
+        mut dependencies: D,
+        mut combine: C,
+    ) -> Option<V>
+    where
+        D: FnMut(&K) -> Vec<K>,
+        C: FnMut(&K, Vec<V>) -> V,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            return Some(value.clone());
+        }
+
+        // Keys on the stack, to tell a cycle from a dependency shared by two keys
+        let mut in_progress = HashSet::from([key.clone()]);
+        let mut stack = vec![(key.clone(), dependencies(&key))];
+
+        while let Some((_, deps)) = stack.last() {
+            let pending = deps
+                .iter()
+                .find(|dep| !self.cache.contains_key(dep))
+                .cloned();
+
+            if let Some(dep) = pending {
+                if !in_progress.insert(dep.clone()) {
+                    return None;
+                }
+                let dep_deps = dependencies(&dep);
+                stack.push((dep, dep_deps));
+            } else if let Some((current, deps)) = stack.pop() {
+                let values = deps.iter().map(|dep| self.cache[dep].clone()).collect();
+                let value = combine(&current, values);
+
+                in_progress.remove(&current);
+                self.cache.insert(current, value);
+            }
+        }
+
+        self.cache.get(&key).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fib(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+        if n < 2 {
+            return n;
+        }
+        memo.get_or_compute(n, |memo| fib(n - 1, memo) + fib(n - 2, memo))
+    }
+
+    #[test]
+    fn test_recursive() {
+        let mut memo = Memo::new();
+        assert_eq!(fib(90, &mut memo), 2_880_067_194_370_816_120);
+        assert_eq!(memo.len(), 89);
+        assert_eq!(memo.get(&10), Some(&55));
+        assert_eq!(memo.get(&1), None);
+    }
+
+    #[test]
+    fn test_iterative() {
+        let mut memo = Memo::new();
+        let mut calls = 0;
+
+        let value = memo.get_or_compute_iterative(
+            90_u64,
+            |&n| {
+                if n < 2 {
+                    Vec::new()
+                } else {
+                    vec![n - 1, n - 2]
+                }
+            },
+            |&n, values| {
+                calls += 1;
+                if n < 2 { n } else { values.iter().sum() }
+            },
+        );
+
+        assert_eq!(value, Some(2_880_067_194_370_816_120));
+        assert_eq!(calls, 91);
+    }
+
+    #[test]
+    fn test_iterative_deep() {
+        // Far deeper than the call stack would allow
+        let mut memo = Memo::new();
+        let length = memo.get_or_compute_iterative(
+            200_000_u32,
+            |&n| if n == 0 { Vec::new() } else { vec![n - 1] },
+            |_, values| values.first().map_or(0, |previous| previous + 1),
+        );
+
+        assert_eq!(length, Some(200_000));
+    }
+
+    #[test]
+    fn test_iterative_cycle() {
+        let mut memo: Memo<u8, u8> = Memo::new();
+        let value = memo.get_or_compute_iterative(0, |&n| vec![(n + 1) % 3], |_, _| 0);
+
+        assert_eq!(value, None);
+    }
+}
@@ -0,0 +1,271 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Error returned by the input parsing helpers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// This number doesn't fit the requested integer type
+    InvalidNumber(String),
+    /// An `open` bracket is never closed
+    Unclosed { open: char, close: char },
+    /// This line has no `:` between its key and its values
+    MissingColon(String),
+    /// A grid row doesn't have the width of the first one
+    RaggedGrid {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidNumber(token) => write!(f, "{token:?} is not a valid number"),
+            ParseError::Unclosed { open, close } => {
+                write!(f, "'{open}' without a matching '{close}'")
+            }
+            ParseError::MissingColon(line) => write!(f, "{line:?} has no ':' after its key"),
+            ParseError::RaggedGrid {
+                row,
+                expected,
+                found,
+            } => write!(f, "grid row {row} is {found} wide instead of {expected}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Splits the input into its blocks of lines separated by blank lines.
+///
+/// Runs of blank lines count as one separator, and sections are returned without their final
+/// line break.
+pub fn sections(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+
+    std::iter::from_fn(move || {
+        // Skip the blank lines before the section
+        loop {
+            if rest.is_empty() {
+                return None;
+            }
+            let line_end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+            if !rest[..line_end].trim().is_empty() {
+                break;
+            }
+            rest = &rest[line_end..];
+        }
+
+        let mut end = 0;
+        while end < rest.len() {
+            let line_end = rest[end..].find('\n').map_or(rest.len(), |i| end + i + 1);
+            if rest[end..line_end].trim().is_empty() {
+                break;
+            }
+            end = line_end;
+        }
+
+        let section = rest[..end].trim_end_matches(['\n', '\r']);
+        rest = &rest[end..];
+
+        Some(section)
+    })
+}
+
+/// Extracts every integer of the text, in order, ignoring whatever lies between them.
+///
+/// A `-` directly before digits is a sign unless it follows a letter or digit, so `x=-3` gives
+/// `-3` while the range `1-3` gives `1` and `3`.
+///
+/// # Errors
+///
+/// Yields `ParseError::InvalidNumber` for integers that don't fit `T` (including negative ones
+/// for unsigned types).
+pub fn ints<T: FromStr>(text: &str) -> impl Iterator<Item = Result<T, ParseError>> + '_ {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    std::iter::from_fn(move || {
+        while i < bytes.len() {
+            let start = i;
+            let signed = bytes[i] == b'-'
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+                && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric());
+            i += 1;
+
+            if signed || bytes[start].is_ascii_digit() {
+                while i < bytes.len() && bytes[i].is_ascii_digit() {
+                    i += 1;
+                }
+                let token = &text[start..i];
+
+                return Some(
+                    token
+                        .parse()
+                        .map_err(|_| ParseError::InvalidNumber(token.to_string())),
+                );
+            }
+        }
+
+        None
+    })
+}
+
+/// Returns what lies between each `open` and the next `close`, e.g. `"0,3"` and `"1"` for
+/// `(0,3) (1)` with parentheses. Brackets don't nest.
+///
+/// # Errors
+///
+/// Yields `ParseError::Unclosed`, and stops, when an `open` has no `close` after it.
+pub fn bracketed(
+    text: &str,
+    open: char,
+    close: char,
+) -> impl Iterator<Item = Result<&str, ParseError>> {
+    let mut rest = Some(text);
+
+    std::iter::from_fn(move || {
+        let text = rest?;
+        let Some(start) = text.find(open) else {
+            rest = None;
+            return None;
+        };
+        let inner = &text[start + open.len_utf8()..];
+
+        if let Some(end) = inner.find(close) {
+            rest = Some(&inner[end + close.len_utf8()..]);
+            Some(Ok(&inner[..end]))
+        } else {
+            rest = None;
+            Some(Err(ParseError::Unclosed { open, close }))
+        }
+    })
+}
+
+/// Splits a `key: values` line at its first `:`, both sides trimmed.
+///
+/// # Errors
+///
+/// Returns `ParseError::MissingColon` if the line has no `:`.
+pub fn key_values(line: &str) -> Result<(&str, &str), ParseError> {
+    line.split_once(':')
+        .map(|(key, values)| (key.trim(), values.trim()))
+        .ok_or_else(|| ParseError::MissingColon(line.to_string()))
+}
+
+/// Parses a rectangular grid of characters, one row per line (trailing blank lines ignored).
+///
+/// # Errors
+///
+/// Returns `ParseError::RaggedGrid` for the first row whose width differs from the first one.
+pub fn char_grid(input: &str) -> Result<Vec<Vec<char>>, ParseError> {
+    let grid: Vec<Vec<char>> = input
+        .trim_end_matches(['\n', '\r'])
+        .lines()
+        .map(|line| line.chars().collect())
+        .collect();
+
+    let expected = grid.first().map_or(0, Vec::len);
+    if let Some((row, line)) = grid
+        .iter()
+        .enumerate()
+        .find(|(_, line)| line.len() != expected)
+    {
+        return Err(ParseError::RaggedGrid {
+            row,
+            expected,
+            found: line.len(),
+        });
+    }
+
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections() {
+        let input = "3-5\n10-14\n\n1\n5\n\n\n\n32\n";
+        let parts: Vec<&str> = sections(input).collect();
+        assert_eq!(parts, vec!["3-5\n10-14", "1\n5", "32"]);
+
+        let windows = "a\r\nb\r\n  \r\nc\r\n";
+        assert_eq!(sections(windows).collect::<Vec<_>>(), vec!["a\r\nb", "c"]);
+
+        assert_eq!(sections("\n\n").count(), 0);
+    }
+
+    #[test]
+    fn test_ints() {
+        let values: Result<Vec<i32>, _> = ints("p=-3,4 v=12,-7").collect();
+        assert_eq!(values, Ok(vec![-3, 4, 12, -7]));
+
+        let range: Result<Vec<u64>, _> = ints("1-3").collect();
+        assert_eq!(range, Ok(vec![1, 3]));
+
+        let dims: Result<Vec<usize>, _> = ints("12x5: 1 0 1 0 3 2").collect();
+        assert_eq!(dims, Ok(vec![12, 5, 1, 0, 1, 0, 3, 2]));
+
+        assert_eq!(
+            ints::<u8>("x=300").next(),
+            Some(Err(ParseError::InvalidNumber("300".to_string())))
+        );
+        assert_eq!(
+            ints::<u8>("x=-3").next(),
+            Some(Err(ParseError::InvalidNumber("-3".to_string())))
+        );
+        assert_eq!(ints::<i32>("no numbers - here").count(), 0);
+    }
+
+    #[test]
+    fn test_bracketed() {
+        let line = "[.##.] (3) (1,3) (2) {3,5,4,7}";
+
+        let buttons: Vec<&str> = bracketed(line, '(', ')').map(Result::unwrap).collect();
+        assert_eq!(buttons, vec!["3", "1,3", "2"]);
+        assert_eq!(bracketed(line, '[', ']').next(), Some(Ok(".##.")));
+        assert_eq!(bracketed(line, '<', '>').next(), None);
+
+        let unclosed: Vec<_> = bracketed("(1) (2", '(', ')').collect();
+        assert_eq!(
+            unclosed,
+            vec![
+                Ok("1"),
+                Err(ParseError::Unclosed {
+                    open: '(',
+                    close: ')'
+                })
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_values() {
+        assert_eq!(key_values("aaa: you hhh"), Ok(("aaa", "you hhh")));
+        assert_eq!(key_values("4x4:0 0 1"), Ok(("4x4", "0 0 1")));
+        assert_eq!(key_values("0:"), Ok(("0", "")));
+        assert_eq!(
+            key_values("nope"),
+            Err(ParseError::MissingColon("nope".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_char_grid() {
+        let grid = char_grid("..@\n@.@\n\n").unwrap();
+        assert_eq!(grid, vec![vec!['.', '.', '@'], vec!['@', '.', '@']]);
+
+        assert_eq!(
+            char_grid("...\n..\n..."),
+            Err(ParseError::RaggedGrid {
+                row: 1,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(char_grid(""), Ok(Vec::new()));
+    }
+}
@@ -1,10 +1,10 @@
-use std::fmt;
-use std::ops::{Add, Sub};
-use std::str::FromStr;
+use std::ops::Neg;
 
+use crate::point_n::{ParsePointError, PointN, impl_via_point_n, parse_coords};
 use crate::points::Number;
 
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+/// Points order by `x`, then `y`, then `z`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Point3D<T: Number> {
     pub x: T,
     pub y: T,
@@ -17,54 +17,71 @@ impl<T: Number> Point3D<T> {
     }
 }
 
-impl<T: Number> Add for Point3D<T> {
-    type Output = Self;
+impl_via_point_n!(Point3D, 3);
 
-    fn add(self, other: Self) -> Self::Output {
+impl<T: Number> Point3D<T> {
+    /// Returns the cross product, perpendicular to both points (right-handed).
+    ///
+    /// Underflows for unsigned types when a component would be negative.
+    #[must_use]
+    pub fn cross(&self, other: &Self) -> Self {
         Point3D {
-            x: self.x + other.x,
-            y: self.y + other.y,
-            z: self.z + other.z,
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
         }
     }
 }
 
-impl<T: Number> Sub for Point3D<T> {
-    type Output = Self;
+/// Rotations are 90 degrees counterclockwise when looking from the positive end of the axis
+/// towards the origin (right-hand rule).
+impl<T: Number + Neg<Output = T>> Point3D<T> {
+    /// Rotates around the x axis: `(x, y, z) -> (x, -z, y)`.
+    #[must_use]
+    pub fn rotate_x(&self) -> Self {
+        Point3D::new(self.x, -self.z, self.y)
+    }
 
-    fn sub(self, other: Self) -> Self::Output {
-        Point3D {
-            x: self.x - other.x,
-            y: self.y - other.y,
-            z: self.z - other.z,
-        }
+    /// Rotates around the y axis: `(x, y, z) -> (z, y, -x)`.
+    #[must_use]
+    pub fn rotate_y(&self) -> Self {
+        Point3D::new(self.z, self.y, -self.x)
     }
-}
 
-impl<T: Number> fmt::Display for Point3D<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    /// Rotates around the z axis: `(x, y, z) -> (-y, x, z)`.
+    #[must_use]
+    pub fn rotate_z(&self) -> Self {
+        Point3D::new(-self.y, self.x, self.z)
     }
-}
 
-impl<T: Number> Point3D<T> {
-    pub fn manhattan_distance(&self, other: &Self) -> T {
-        let dx = if self.x > other.x {
-            self.x - other.x
-        } else {
-            other.x - self.x
-        };
-        let dy = if self.y > other.y {
-            self.y - other.y
-        } else {
-            other.y - self.y
-        };
-        let dz = if self.z > other.z {
-            self.z - other.z
-        } else {
-            other.z - self.z
-        };
-        dx + dy + dz
+    /// Applies one of the 24 rotations of the cube, `orientation` being taken modulo 24.
+    ///
+    /// The same index applies the same rotation to every point, and index 0 is the identity.
+    /// Orientations `4 * f..4 * f + 4` share where the x axis ends up (`+x`, `+y`, `-x`, `-y`,
+    /// `-z`, `+z` for `f` from 0 to 5) and differ by rotations around it.
+    #[must_use]
+    pub fn rotate(&self, orientation: usize) -> Self {
+        let orientation = orientation % 24;
+
+        let mut point = *self;
+        for _ in 0..orientation % 4 {
+            point = point.rotate_x();
+        }
+
+        match orientation / 4 {
+            0 => point,
+            1 => point.rotate_z(),
+            2 => point.rotate_z().rotate_z(),
+            3 => point.rotate_z().rotate_z().rotate_z(),
+            4 => point.rotate_y(),
+            _ => point.rotate_y().rotate_y().rotate_y(),
+        }
+    }
+
+    /// Returns the point under each of the 24 rotations, in the order of `rotate`.
+    #[must_use]
+    pub fn orientations(&self) -> [Self; 24] {
+        std::array::from_fn(|orientation| self.rotate(orientation))
     }
 }
 
@@ -132,34 +149,6 @@ impl<T: Number> From<Point3D<T>> for (T, T, T) {
     }
 }
 
-impl<T> FromStr for Point3D<T>
-where
-    T: Number + FromStr,
-    T::Err: std::fmt::Debug,
-{
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split_whitespace().collect();
-
-        if parts.len() < 3 {
-            return Err("Invalid point3d format".to_string());
-        }
-
-        let x = parts[0]
-            .parse()
-            .map_err(|e| format!("Failed to parse x coordinate: {e:?}"))?;
-        let y = parts[1]
-            .parse()
-            .map_err(|e| format!("Failed to parse y coordinate: {e:?}"))?;
-        let z = parts[2]
-            .parse()
-            .map_err(|e| format!("Failed to parse z coordinate: {e:?}"))?;
-
-        Ok(Point3D { x, y, z })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -237,6 +226,86 @@ mod tests {
         assert_eq!(p7.manhattan_distance(&p8), 0);
     }
 
+    #[test]
+    fn test_scalar_ops() {
+        let mut p = Point3D::new(2, -4, 6);
+
+        assert_eq!(p * 3, Point3D::new(6, -12, 18));
+        assert_eq!(p / 2, Point3D::new(1, -2, 3));
+        assert_eq!(-p, Point3D::new(-2, 4, -6));
+
+        p += Point3D::new(1, 1, 1);
+        assert_eq!(p, Point3D::new(3, -3, 7));
+        p -= Point3D::new(3, -3, 7);
+        assert_eq!(p, Point3D::new(0, 0, 0));
+    }
+
+    #[test]
+    fn test_dot_cross() {
+        let x = Point3D::new(1, 0, 0);
+        let y = Point3D::new(0, 1, 0);
+
+        assert_eq!(x.cross(&y), Point3D::new(0, 0, 1));
+        assert_eq!(y.cross(&x), Point3D::new(0, 0, -1));
+        assert_eq!(x.dot(&y), 0);
+
+        let a = Point3D::new(1, 2, 3);
+        let b = Point3D::new(4, 5, 6);
+        assert_eq!(a.dot(&b), 32);
+        assert_eq!(a.cross(&b), Point3D::new(-3, 6, -3));
+        assert_eq!(a.cross(&b).dot(&a), 0);
+    }
+
+    #[test]
+    fn test_axis_rotations() {
+        let p = Point3D::new(1, 2, 3);
+
+        assert_eq!(p.rotate_x(), Point3D::new(1, -3, 2));
+        assert_eq!(p.rotate_y(), Point3D::new(3, 2, -1));
+        assert_eq!(p.rotate_z(), Point3D::new(-2, 1, 3));
+
+        // Rotating the x axis around z gives the y axis, and so on
+        let (x, y, z) = (
+            Point3D::new(1, 0, 0),
+            Point3D::new(0, 1, 0),
+            Point3D::new(0, 0, 1),
+        );
+        assert_eq!(x.rotate_z(), y);
+        assert_eq!(y.rotate_x(), z);
+        assert_eq!(z.rotate_y(), x);
+
+        assert_eq!(p.rotate_x().rotate_x().rotate_x().rotate_x(), p);
+    }
+
+    #[test]
+    fn test_orientations() {
+        use std::collections::HashSet;
+
+        let p = Point3D::new(1, 2, 3);
+        let orientations = p.orientations();
+
+        assert_eq!(orientations[0], p);
+        assert_eq!(orientations.iter().collect::<HashSet<_>>().len(), 24);
+
+        // Rotations preserve lengths and handedness
+        let (x, y) = (Point3D::new(1, 0, 0), Point3D::new(0, 1, 0));
+        for orientation in 0..24 {
+            let rotated = p.rotate(orientation);
+            assert_eq!(rotated.dot(&rotated), p.dot(&p));
+            assert_eq!(
+                x.rotate(orientation).cross(&y.rotate(orientation)),
+                Point3D::new(0, 0, 1).rotate(orientation)
+            );
+        }
+    }
+
+    #[test]
+    fn test_chebyshev_distance() {
+        let p1 = Point3D::new(1, 1, 1);
+        let p2 = Point3D::new(4, -5, 7);
+        assert_eq!(p1.chebyshev_distance(&p2), 6);
+    }
+
     #[test]
     fn test_distance_squared() {
         let p1 = Point3D::new(1i32, 2i32, 3i32);
@@ -344,5 +413,10 @@ mod tests {
 
         let result: Result<Point3D<i32>, _> = "1 2".parse();
         assert!(result.is_err());
+
+        let point: Point3D<i64> = "162,817,812".parse().unwrap();
+        assert_eq!(point, Point3D::new(162, 817, 812));
+        assert_eq!("z=3, x=1, y=2".parse(), Ok(Point3D::new(1, 2, 3)));
+        assert_eq!(point.to_string().parse(), Ok(point));
     }
 }
@@ -0,0 +1,531 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Index, IndexMut, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::point3d::Point3D;
+use crate::points::{Number, Point};
+
+/// Point with `N` coordinates, for when the dimension is a parameter of the puzzle.
+///
+/// `Point` and `Point3D` keep their named fields and convert to and from `PointN<T, 2>` and
+/// `PointN<T, 3>`, sharing its arithmetic, distance metrics, `Display` and `FromStr`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PointN<T: Number, const N: usize> {
+    pub coords: [T; N],
+}
+
+pub type Point4D<T> = PointN<T, 4>;
+
+impl<T: Number, const N: usize> PointN<T, N> {
+    pub fn new(coords: [T; N]) -> Self {
+        PointN { coords }
+    }
+
+    pub fn origin() -> Self {
+        PointN::new([T::ZERO; N])
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        self.axis_distances(other)
+            .fold(T::ZERO, |total, distance| total + distance)
+    }
+
+    /// Returns the largest of the distances along each axis.
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        self.axis_distances(other).fold(
+            T::ZERO,
+            |max, distance| if distance > max { distance } else { max },
+        )
+    }
+
+    pub fn dot(&self, other: &Self) -> T {
+        self.coords
+            .iter()
+            .zip(&other.coords)
+            .fold(T::ZERO, |total, (&a, &b)| total + a * b)
+    }
+
+    /// Absolute differences along each axis, computed without underflowing unsigned types.
+    fn axis_distances<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = T> + 'a {
+        self.coords
+            .iter()
+            .zip(&other.coords)
+            .map(|(&a, &b)| if a > b { a - b } else { b - a })
+    }
+}
+
+impl<T: Number + Neg<Output = T>, const N: usize> PointN<T, N> {
+    /// Returns the `2 * N` neighbors one step away along a single axis.
+    #[must_use]
+    pub fn orthogonal_neighbors(&self) -> Vec<Self> {
+        (0..N)
+            .flat_map(|axis| {
+                [-T::ONE, T::ONE].map(|delta| {
+                    let mut neighbor = *self;
+                    neighbor.coords[axis] = neighbor.coords[axis] + delta;
+                    neighbor
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the `3^N - 1` surrounding neighbors (every axis moving by -1, 0 or 1), ordered
+    /// by the offset along the first axis, then the second, and so on.
+    #[must_use]
+    pub fn neighbors(&self) -> Vec<Self> {
+        let mut offsets = vec![[T::ZERO; N]];
+        for axis in 0..N {
+            offsets = offsets
+                .into_iter()
+                .flat_map(|offset| {
+                    [-T::ONE, T::ZERO, T::ONE].map(|delta| {
+                        let mut next = offset;
+                        next[axis] = delta;
+                        next
+                    })
+                })
+                .collect();
+        }
+
+        offsets
+            .into_iter()
+            .filter(|offset| offset.iter().any(|&delta| delta != T::ZERO))
+            .map(|offset| *self + PointN::new(offset))
+            .collect()
+    }
+}
+
+impl<T: Number, const N: usize> Index<usize> for PointN<T, N> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &T {
+        &self.coords[axis]
+    }
+}
+
+impl<T: Number, const N: usize> IndexMut<usize> for PointN<T, N> {
+    fn index_mut(&mut self, axis: usize) -> &mut T {
+        &mut self.coords[axis]
+    }
+}
+
+impl<T: Number, const N: usize> Add for PointN<T, N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        PointN::new(std::array::from_fn(|i| self.coords[i] + other.coords[i]))
+    }
+}
+
+impl<T: Number, const N: usize> Sub for PointN<T, N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self::Output {
+        PointN::new(std::array::from_fn(|i| self.coords[i] - other.coords[i]))
+    }
+}
+
+impl<T: Number, const N: usize> AddAssign for PointN<T, N> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Number, const N: usize> SubAssign for PointN<T, N> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Number, const N: usize> Mul<T> for PointN<T, N> {
+    type Output = Self;
+
+    fn mul(self, scalar: T) -> Self::Output {
+        PointN::new(self.coords.map(|v| v * scalar))
+    }
+}
+
+impl<T: Number, const N: usize> Div<T> for PointN<T, N> {
+    type Output = Self;
+
+    fn div(self, scalar: T) -> Self::Output {
+        PointN::new(self.coords.map(|v| v / scalar))
+    }
+}
+
+impl<T: Number + Neg<Output = T>, const N: usize> Neg for PointN<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        PointN::new(self.coords.map(|v| -v))
+    }
+}
+
+impl<T: Number, const N: usize> fmt::Display for PointN<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(")?;
+        for (i, value) in self.coords.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{value}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl<T: Number, const N: usize> From<[T; N]> for PointN<T, N> {
+    fn from(coords: [T; N]) -> Self {
+        PointN { coords }
+    }
+}
+
+impl<T: Number, const N: usize> From<PointN<T, N>> for [T; N] {
+    fn from(point: PointN<T, N>) -> [T; N] {
+        point.coords
+    }
+}
+
+impl<T: Number> From<Point<T>> for PointN<T, 2> {
+    fn from(point: Point<T>) -> Self {
+        PointN::new([point.x, point.y])
+    }
+}
+
+impl<T: Number> From<PointN<T, 2>> for Point<T> {
+    fn from(PointN { coords: [x, y] }: PointN<T, 2>) -> Self {
+        Point::new(x, y)
+    }
+}
+
+impl<T: Number> From<Point3D<T>> for PointN<T, 3> {
+    fn from(point: Point3D<T>) -> Self {
+        PointN::new([point.x, point.y, point.z])
+    }
+}
+
+impl<T: Number> From<PointN<T, 3>> for Point3D<T> {
+    fn from(PointN { coords: [x, y, z] }: PointN<T, 3>) -> Self {
+        Point3D::new(x, y, z)
+    }
+}
+
+/// Implements the arithmetic operators, distance metrics, `Display` and `FromStr` of a point
+/// type with named fields by converting it to and from `PointN<T, $n>`, so the code is shared.
+macro_rules! impl_via_point_n {
+    ($point:ident, $n:literal) => {
+        impl<T: Number> $point<T> {
+            pub fn manhattan_distance(&self, other: &Self) -> T {
+                PointN::<T, $n>::from(*self).manhattan_distance(&PointN::from(*other))
+            }
+
+            /// Returns the largest of the distances along each axis (king moves on a grid).
+            pub fn chebyshev_distance(&self, other: &Self) -> T {
+                PointN::<T, $n>::from(*self).chebyshev_distance(&PointN::from(*other))
+            }
+
+            pub fn dot(&self, other: &Self) -> T {
+                PointN::<T, $n>::from(*self).dot(&PointN::from(*other))
+            }
+        }
+
+        impl<T: Number> std::ops::Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self::Output {
+                (PointN::<T, $n>::from(self) + PointN::from(other)).into()
+            }
+        }
+
+        impl<T: Number> std::ops::Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self::Output {
+                (PointN::<T, $n>::from(self) - PointN::from(other)).into()
+            }
+        }
+
+        impl<T: Number> std::ops::AddAssign for $point<T> {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl<T: Number> std::ops::SubAssign for $point<T> {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+
+        impl<T: Number> std::ops::Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, scalar: T) -> Self::Output {
+                (PointN::<T, $n>::from(self) * scalar).into()
+            }
+        }
+
+        impl<T: Number> std::ops::Div<T> for $point<T> {
+            type Output = Self;
+
+            fn div(self, scalar: T) -> Self::Output {
+                (PointN::<T, $n>::from(self) / scalar).into()
+            }
+        }
+
+        impl<T: Number + std::ops::Neg<Output = T>> std::ops::Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                (-PointN::<T, $n>::from(self)).into()
+            }
+        }
+
+        impl<T: Number> std::fmt::Display for $point<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                PointN::<T, $n>::from(*self).fmt(f)
+            }
+        }
+
+        /// Parses the coordinates separated by commas and/or whitespace (`1,2`, `1, 2`, `1 2`),
+        /// named (`x=1, y=2`, in any order) or parenthesized like `Display` writes them
+        /// (`(1, 2)`).
+        impl<T: Number + std::str::FromStr> std::str::FromStr for $point<T> {
+            type Err = ParsePointError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                parse_coords::<T, $n>(s).map(|coords| PointN::new(coords).into())
+            }
+        }
+    };
+}
+
+pub(crate) use impl_via_point_n;
+
+/// Error returned when parsing a `Point`, `Point3D` or `PointN` fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParsePointError {
+    /// The text holds `found` coordinates instead of `expected`
+    WrongCount { expected: usize, found: usize },
+    /// This coordinate doesn't parse as a number
+    InvalidNumber(String),
+    /// A `key=value` coordinate names an unknown axis, or one given twice
+    InvalidAxis(String),
+    /// Some coordinates are named with `key=value` and others aren't
+    MixedNaming,
+}
+
+impl fmt::Display for ParsePointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePointError::WrongCount { expected, found } => {
+                write!(f, "expected {expected} coordinates, found {found}")
+            }
+            ParsePointError::InvalidNumber(value) => write!(f, "{value:?} is not a number"),
+            ParsePointError::InvalidAxis(key) => write!(f, "{key:?} is not a valid axis here"),
+            ParsePointError::MixedNaming => write!(f, "either all or no coordinates must be named"),
+        }
+    }
+}
+
+impl std::error::Error for ParsePointError {}
+
+/// Axis names accepted in `key=value` coordinates, in order.
+const AXIS_NAMES: [&str; 4] = ["x", "y", "z", "w"];
+
+/// Parses `N` coordinates in any of the forms accepted by the points' `FromStr`.
+///
+/// Coordinates are separated by commas and/or whitespace, and may be wrapped in `()`, `[]` or
+/// `<>`. An empty field between commas is an invalid number. They are either all positional or
+/// all `key=value`, keys being the first `N` of `x`, `y`, `z`, `w` in any order and case.
+pub(crate) fn parse_coords<T: Number + FromStr, const N: usize>(
+    s: &str,
+) -> Result<[T; N], ParsePointError> {
+    let mut inner = s.trim();
+    for (open, close) in [('(', ')'), ('[', ']'), ('<', '>')] {
+        if let Some(stripped) = inner
+            .strip_prefix(open)
+            .and_then(|rest| rest.strip_suffix(close))
+        {
+            inner = stripped.trim();
+            break;
+        }
+    }
+
+    let mut parts = Vec::new();
+    if !inner.is_empty() {
+        for field in inner.split(',') {
+            if field.trim().is_empty() {
+                return Err(ParsePointError::InvalidNumber(String::new()));
+            }
+
+            // Spaces around `=` don't separate coordinates
+            let compact = field
+                .split('=')
+                .map(str::trim)
+                .collect::<Vec<_>>()
+                .join("=");
+            parts.extend(compact.split_whitespace().map(str::to_string));
+        }
+    }
+
+    if parts.len() != N {
+        return Err(ParsePointError::WrongCount {
+            expected: N,
+            found: parts.len(),
+        });
+    }
+
+    let number = |text: &str| {
+        text.trim()
+            .parse()
+            .map_err(|_| ParsePointError::InvalidNumber(text.trim().to_string()))
+    };
+
+    let mut coords = [T::ZERO; N];
+    match parts.iter().filter(|part| part.contains('=')).count() {
+        0 => {
+            for (coord, part) in coords.iter_mut().zip(&parts) {
+                *coord = number(part)?;
+            }
+        }
+        named if named == N => {
+            let names = AXIS_NAMES.get(..N).unwrap_or(&[]);
+            let mut given = [false; N];
+
+            for part in &parts {
+                let (key, value) = part.split_once('=').unwrap_or((part, ""));
+                let key = key.trim();
+                let axis = names
+                    .iter()
+                    .position(|name| name.eq_ignore_ascii_case(key))
+                    .filter(|&axis| !given[axis])
+                    .ok_or_else(|| ParsePointError::InvalidAxis(key.to_string()))?;
+
+                coords[axis] = number(value)?;
+                given[axis] = true;
+            }
+        }
+        _ => return Err(ParsePointError::MixedNaming),
+    }
+
+    Ok(coords)
+}
+
+/// Parses `N` coordinates in the same forms as `Point` (`key=value` axes being `x`, `y`, `z`
+/// and `w` up to 4 dimensions).
+impl<T: Number + FromStr, const N: usize> FromStr for PointN<T, N> {
+    type Err = ParsePointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_coords(s).map(PointN::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = PointN::new([1, 2, 3, 4]);
+        let b = PointN::new([4, 3, 2, 1]);
+
+        assert_eq!(a + b, PointN::new([5, 5, 5, 5]));
+        assert_eq!(a - b, PointN::new([-3, -1, 1, 3]));
+        assert_eq!(a * 2, PointN::new([2, 4, 6, 8]));
+        assert_eq!(-a / 2, PointN::new([0, -1, -1, -2]));
+        assert_eq!(a.dot(&b), 20);
+        assert_eq!(a[3], 4);
+
+        let mut c = PointN::<i32, 4>::origin();
+        c += a;
+        c[0] = 10;
+        assert_eq!(c, PointN::new([10, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = PointN::new([1u32, 8, 3, 0]);
+        let b = PointN::new([4, 5, 3, 7]);
+
+        assert_eq!(a.manhattan_distance(&b), 13);
+        assert_eq!(a.chebyshev_distance(&b), 7);
+    }
+
+    #[test]
+    fn test_named_points_convert() {
+        let p: PointN<i64, 2> = Point::new(3, -4).into();
+        assert_eq!(p, PointN::new([3, -4]));
+        assert_eq!(Point::from(p), Point::new(3, -4));
+
+        let q: PointN<i64, 3> = Point3D::new(1, 2, 3).into();
+        assert_eq!(Point3D::from(q), Point3D::new(1, 2, 3));
+        assert_eq!(format!("{q}"), "(1, 2, 3)");
+    }
+
+    #[test]
+    fn test_neighbors_4d() {
+        let p = Point4D::new([0, 0, 0, 0]);
+        let neighbors = p.neighbors();
+
+        assert_eq!(neighbors.len(), 80);
+        assert_eq!(neighbors[0], Point4D::new([-1, -1, -1, -1]));
+        assert!(!neighbors.contains(&p));
+        assert!(neighbors.iter().all(|n| n.chebyshev_distance(&p) == 1));
+
+        let orthogonal = p.orthogonal_neighbors();
+        assert_eq!(orthogonal.len(), 8);
+        assert!(orthogonal.iter().all(|n| n.manhattan_distance(&p) == 1));
+
+        // Matches the 3D helper
+        let q = PointN::new([1, 2, 3]);
+        let expected: Vec<PointN<i32, 3>> = Point3D::new(1, 2, 3)
+            .neighbors26()
+            .into_iter()
+            .map(PointN::from)
+            .collect();
+        assert_eq!(q.neighbors(), expected);
+    }
+
+    #[test]
+    fn test_from_str() {
+        let p: Point4D<i32> = "1,-2, 3 4".parse().unwrap();
+        assert_eq!(p, Point4D::new([1, -2, 3, 4]));
+        assert_eq!("<1 -2 3 4>".parse(), Ok(p));
+        assert_eq!("w=4, z=3, y=-2, x=1".parse(), Ok(p));
+        assert_eq!(p.to_string().parse(), Ok(p));
+
+        assert_eq!(
+            "1,2,3".parse::<Point4D<i32>>(),
+            Err(ParsePointError::WrongCount {
+                expected: 4,
+                found: 3
+            })
+        );
+        assert_eq!(
+            "1,2,x,4".parse::<Point4D<i32>>(),
+            Err(ParsePointError::InvalidNumber("x".to_string()))
+        );
+        assert_eq!("x=1 y=-2 z=3 w=4".parse(), Ok(p));
+
+        // Empty fields are missing numbers, not a wrong count
+        for text in ["1,,2", "1,2,"] {
+            assert_eq!(
+                text.parse::<PointN<i32, 2>>(),
+                Err(ParsePointError::InvalidNumber(String::new())),
+                "{text}"
+            );
+        }
+        assert_eq!(
+            "".parse::<PointN<i32, 2>>(),
+            Err(ParsePointError::WrongCount {
+                expected: 2,
+                found: 0
+            })
+        );
+
+        let five: PointN<u8, 5> = "1 2 3 4 5".parse().unwrap();
+        assert_eq!(five[4], 5);
+    }
+}
@@ -1,29 +1,39 @@
 use std::fmt;
-use std::ops::{Add, Sub};
-use std::str::FromStr;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::point_n::{ParsePointError, PointN, impl_via_point_n, parse_coords};
 
 // Define a trait that combines the necessary numeric traits
 pub trait Number:
-    Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> + fmt::Display
+    Copy
+    + PartialOrd
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + fmt::Display
 {
+    const ZERO: Self;
+    const ONE: Self;
 }
 
 // Implement the Number trait for the built-in numeric types
-impl Number for i8 {}
-impl Number for i16 {}
-impl Number for i32 {}
-impl Number for i64 {}
-impl Number for i128 {}
-impl Number for u8 {}
-impl Number for u16 {}
-impl Number for u32 {}
-impl Number for u64 {}
-impl Number for u128 {}
-impl Number for f32 {}
-impl Number for f64 {}
-impl Number for usize {}
-
-#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+macro_rules! impl_number {
+    ($zero:literal, $one:literal => $($t:ty),*) => {
+        $(
+            impl Number for $t {
+                const ZERO: Self = $zero;
+                const ONE: Self = $one;
+            }
+        )*
+    };
+}
+
+impl_number!(0, 1 => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_number!(0.0, 1.0 => f32, f64);
+
+/// Points order by `x`, then `y`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Point<T: Number> {
     pub x: T,
     pub y: T,
@@ -36,47 +46,91 @@ impl<T: Number> Point<T> {
     }
 }
 
-impl<T: Number> Add for Point<T> {
-    type Output = Self;
+impl_via_point_n!(Point, 2);
 
-    fn add(self, other: Self) -> Self::Output {
-        Point {
-            x: self.x + other.x,
-            y: self.y + other.y,
-        }
+impl<T: Number> Point<T> {
+    /// Returns the z component of the 3D cross product, `x1 * y2 - y1 * x2`.
+    ///
+    /// Positive when `other` is counterclockwise from `self` (y axis pointing up). Underflows
+    /// for unsigned types when the result would be negative.
+    pub fn cross(&self, other: &Self) -> T {
+        self.x * other.y - self.y * other.x
     }
-}
 
-impl<T: Number> Sub for Point<T> {
-    type Output = Self;
+    /// Moves one unit along each axis towards `target`, diagonally if both coordinates differ.
+    ///
+    /// Returns the point itself once it reaches `target`. Never underflows, since the moves
+    /// only go towards another valid point.
+    #[must_use]
+    pub fn step_toward(&self, target: &Self) -> Self {
+        let step = |from: T, to: T| {
+            if to > from {
+                from + T::ONE
+            } else if to < from {
+                from - T::ONE
+            } else {
+                from
+            }
+        };
 
-    fn sub(self, other: Self) -> Self::Output {
-        Point {
-            x: self.x - other.x,
-            y: self.y - other.y,
-        }
+        Point::new(step(self.x, target.x), step(self.y, target.y))
+    }
+
+    /// Converts the coordinates to another numeric type, losslessly.
+    pub fn cast<U: Number + From<T>>(self) -> Point<U> {
+        Point::new(U::from(self.x), U::from(self.y))
     }
-}
 
-impl<T: Number> fmt::Display for Point<T> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "({}, {})", self.x, self.y)
+    /// Converts the coordinates to another numeric type, or `None` if either doesn't fit.
+    pub fn try_cast<U: Number + TryFrom<T>>(self) -> Option<Point<U>> {
+        Some(Point::new(
+            U::try_from(self.x).ok()?,
+            U::try_from(self.y).ok()?,
+        ))
     }
 }
 
-impl<T: Number> Point<T> {
-    pub fn manhattan_distance(&self, other: &Self) -> T {
-        let dx = if self.x > other.x {
-            self.x - other.x
-        } else {
-            other.x - self.x
-        };
-        let dy = if self.y > other.y {
-            self.y - other.y
-        } else {
-            other.y - self.y
+/// Rotations follow the math convention (y axis pointing up); with screen coordinates (y axis
+/// pointing down) the two directions appear swapped.
+impl<T: Number + Neg<Output = T>> Point<T> {
+    /// Returns the sign of each coordinate, as `-1`, `0` or `1`.
+    #[must_use]
+    pub fn signum(&self) -> Self {
+        let sign = |v: T| {
+            if v > T::ZERO {
+                T::ONE
+            } else if v < T::ZERO {
+                -T::ONE
+            } else {
+                T::ZERO
+            }
         };
-        dx + dy
+
+        Point::new(sign(self.x), sign(self.y))
+    }
+
+    /// Rotates 90 degrees counterclockwise around the origin: `(x, y) -> (-y, x)`.
+    #[must_use]
+    pub fn rotate_counterclockwise(&self) -> Self {
+        Point::new(-self.y, self.x)
+    }
+
+    /// Rotates 90 degrees clockwise around the origin: `(x, y) -> (y, -x)`.
+    #[must_use]
+    pub fn rotate_clockwise(&self) -> Self {
+        Point::new(self.y, -self.x)
+    }
+
+    /// Rotates 90 degrees counterclockwise around `pivot`.
+    #[must_use]
+    pub fn rotate_counterclockwise_around(&self, pivot: &Self) -> Self {
+        (*self - *pivot).rotate_counterclockwise() + *pivot
+    }
+
+    /// Rotates 90 degrees clockwise around `pivot`.
+    #[must_use]
+    pub fn rotate_clockwise_around(&self, pivot: &Self) -> Self {
+        (*self - *pivot).rotate_clockwise() + *pivot
     }
 }
 
@@ -104,31 +158,6 @@ impl<T: Number> From<Point<T>> for (T, T) {
     }
 }
 
-impl<T> FromStr for Point<T>
-where
-    T: Number + FromStr,
-    T::Err: std::fmt::Debug,
-{
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split_whitespace().collect();
-
-        if parts.len() < 2 {
-            return Err("Invalid point format".to_string());
-        }
-
-        let x = parts[0]
-            .parse()
-            .map_err(|e| format!("Failed to parse x coordinate: {e:?}"))?;
-        let y = parts[1]
-            .parse()
-            .map_err(|e| format!("Failed to parse y coordinate: {e:?}"))?;
-
-        Ok(Point { x, y })
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -213,6 +242,127 @@ mod tests {
         assert_eq!(p7.manhattan_distance(&p8), 0);
     }
 
+    #[test]
+    fn test_scalar_ops() {
+        let mut p = Point::new(3, -4);
+
+        assert_eq!(p * 2, Point::new(6, -8));
+        assert_eq!(p / 2, Point::new(1, -2));
+        assert_eq!(-p, Point::new(-3, 4));
+
+        p += Point::new(1, 1);
+        assert_eq!(p, Point::new(4, -3));
+        p -= Point::new(4, 4);
+        assert_eq!(p, Point::new(0, -7));
+    }
+
+    #[test]
+    fn test_dot_cross() {
+        let a = Point::new(2, 3);
+        let b = Point::new(4, -1);
+
+        assert_eq!(a.dot(&b), 5);
+        assert_eq!(a.cross(&b), -14);
+        assert_eq!(b.cross(&a), 14);
+        assert_eq!(a.cross(&(a * 3)), 0);
+    }
+
+    #[test]
+    fn test_rotations() {
+        let p = Point::new(3, 1);
+
+        assert_eq!(p.rotate_counterclockwise(), Point::new(-1, 3));
+        assert_eq!(p.rotate_clockwise(), Point::new(1, -3));
+        assert_eq!(p.rotate_clockwise().rotate_counterclockwise(), p);
+
+        let pivot = Point::new(2, 2);
+        assert_eq!(p.rotate_counterclockwise_around(&pivot), Point::new(3, 3));
+        assert_eq!(p.rotate_clockwise_around(&pivot), Point::new(1, 1));
+    }
+
+    #[test]
+    fn test_chebyshev_distance() {
+        assert_eq!(Point::new(1, 1).chebyshev_distance(&Point::new(4, 5)), 4);
+        assert_eq!(Point::new(-2, 3).chebyshev_distance(&Point::new(5, 0)), 7);
+        assert_eq!(Point::new(2u8, 2).chebyshev_distance(&Point::new(0, 1)), 2);
+    }
+
+    #[test]
+    fn test_signum_and_step_toward() {
+        assert_eq!(Point::new(-7, 0).signum(), Point::new(-1, 0));
+        assert_eq!(Point::new(3.5, -0.5).signum(), Point::new(1.0, -1.0));
+
+        let target = Point::new(0u32, 5);
+        let mut p = Point::new(3u32, 4);
+        let mut path = vec![p];
+        while p != target {
+            p = p.step_toward(&target);
+            path.push(p);
+        }
+        assert_eq!(
+            path,
+            vec![
+                Point::new(3, 4),
+                Point::new(2, 5),
+                Point::new(1, 5),
+                Point::new(0, 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_casts() {
+        let p = Point::new(3u8, 200u8);
+
+        assert_eq!(p.cast::<i32>(), Point::new(3i32, 200i32));
+        assert_eq!(p.try_cast::<i8>(), None);
+        assert_eq!(Point::new(-1i64, 2).try_cast::<usize>(), None);
+        assert_eq!(
+            Point::new(7i64, 2).try_cast::<usize>(),
+            Some(Point::new(7usize, 2))
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        let expected = Point::new(3, -4);
+
+        for text in [
+            "3,-4",
+            "3, -4",
+            " 3 -4 ",
+            "(3, -4)",
+            "x=3,y=-4",
+            "y = -4, X = 3",
+        ] {
+            assert_eq!(text.parse(), Ok(expected), "{text}");
+        }
+        assert_eq!(expected.to_string().parse(), Ok(expected));
+
+        let float: Point<f64> = "(1.5, 2.5)".parse().unwrap();
+        assert_eq!(float.to_string().parse(), Ok(float));
+
+        assert_eq!(
+            "1,2,3".parse::<Point<i32>>(),
+            Err(ParsePointError::WrongCount {
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            "x=1,x=2".parse::<Point<i32>>(),
+            Err(ParsePointError::InvalidAxis("x".to_string()))
+        );
+        assert_eq!(
+            "x=1,2".parse::<Point<i32>>(),
+            Err(ParsePointError::MixedNaming)
+        );
+        assert_eq!(
+            "-1,2".parse::<Point<u32>>(),
+            Err(ParsePointError::InvalidNumber("-1".to_string()))
+        );
+    }
+
     #[test]
     fn test_from_tuple() {
         // Test From<(T, T)>
@@ -0,0 +1,111 @@
+use crate::points::Number;
+
+/// 2D prefix sums over a grid, answering rectangle sums in constant time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefixSum2D<T: Number> {
+    /// `(height + 1) x (width + 1)` table, `sums[r][c]` being the sum of the cells above and
+    /// left of `(r, c)`, stored row by row
+    sums: Vec<T>,
+    height: usize,
+    width: usize,
+}
+
+impl<T: Number> PrefixSum2D<T> {
+    /// Builds the prefix sums of a grid given as rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows do not all have the same length.
+    #[must_use]
+    pub fn new(grid: &[Vec<T>]) -> Self {
+        let width = grid.first().map_or(0, Vec::len);
+        assert!(
+            grid.iter().all(|row| row.len() == width),
+            "all rows must have the same length"
+        );
+
+        Self::from_fn(grid.len(), width, |row, col| grid[row][col])
+    }
+
+    /// Builds the prefix sums of a `height x width` grid whose cells are given by `cell(row, col)`.
+    pub fn from_fn(height: usize, width: usize, mut cell: impl FnMut(usize, usize) -> T) -> Self {
+        let stride = width + 1;
+        let mut sums = vec![T::ZERO; (height + 1) * stride];
+
+        for row in 0..height {
+            let mut row_sum = T::ZERO;
+            for col in 0..width {
+                row_sum = row_sum + cell(row, col);
+                sums[(row + 1) * stride + col + 1] = sums[row * stride + col + 1] + row_sum;
+            }
+        }
+
+        PrefixSum2D {
+            sums,
+            height,
+            width,
+        }
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the sum of the cells in rows `top..=bottom` and columns `left..=right`.
+    ///
+    /// Terms are added before subtracting, so unsigned types never underflow.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rectangle is empty or does not fit in the grid.
+    #[must_use]
+    pub fn sum(&self, top: usize, left: usize, bottom: usize, right: usize) -> T {
+        assert!(
+            top <= bottom && left <= right && bottom < self.height && right < self.width,
+            "rectangle ({top}, {left})-({bottom}, {right}) is outside the {}x{} grid",
+            self.height,
+            self.width
+        );
+
+        let at = |row: usize, col: usize| self.sums[row * (self.width + 1) + col];
+
+        (at(bottom + 1, right + 1) + at(top, left)) - (at(top, right + 1) + at(bottom + 1, left))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sum() {
+        let prefix = PrefixSum2D::new(&[vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
+
+        assert_eq!(prefix.sum(0, 0, 2, 2), 45);
+        assert_eq!(prefix.sum(1, 1, 1, 1), 5);
+        assert_eq!(prefix.sum(0, 1, 1, 2), 16);
+        assert_eq!(prefix.sum(2, 0, 2, 2), 24);
+    }
+
+    #[test]
+    fn test_from_fn_unsigned() {
+        let prefix = PrefixSum2D::from_fn(4, 5, |row, col| u32::from(row == col));
+
+        assert_eq!(prefix.sum(0, 0, 3, 4), 4);
+        assert_eq!(prefix.sum(1, 0, 3, 0), 0);
+        assert_eq!(prefix.sum(1, 1, 2, 2), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "outside")]
+    fn test_sum_out_of_bounds() {
+        let prefix = PrefixSum2D::new(&[vec![1, 2], vec![3, 4]]);
+        let _ = prefix.sum(0, 0, 2, 1);
+    }
+}
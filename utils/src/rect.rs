@@ -0,0 +1,422 @@
+use std::fmt;
+
+use crate::point3d::Point3D;
+use crate::points::{Number, Point};
+
+fn min_of<T: Number>(a: T, b: T) -> T {
+    if b < a { b } else { a }
+}
+
+fn max_of<T: Number>(a: T, b: T) -> T {
+    if b > a { b } else { a }
+}
+
+/// Iterates from `start` to `end` inclusive by steps of one.
+fn steps<T: Number>(start: T, end: T) -> impl Iterator<Item = T> + Clone {
+    std::iter::successors((start <= end).then_some(start), move |&v| {
+        (v < end).then(|| v + T::ONE)
+    })
+}
+
+/// Axis-aligned rectangle with both corners included, always stored as `(min, max)`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Rect<T: Number> {
+    min: Point<T>,
+    max: Point<T>,
+}
+
+impl<T: Number> Rect<T> {
+    /// Creates the rectangle with opposite corners `a` and `b`, in any order.
+    #[must_use]
+    pub fn new(a: Point<T>, b: Point<T>) -> Self {
+        Rect {
+            min: Point::new(min_of(a.x, b.x), min_of(a.y, b.y)),
+            max: Point::new(max_of(a.x, b.x), max_of(a.y, b.y)),
+        }
+    }
+
+    /// Returns the smallest rectangle containing every point, or `None` if there are none.
+    pub fn bounding_box(points: impl IntoIterator<Item = Point<T>>) -> Option<Self> {
+        points
+            .into_iter()
+            .map(|p| Rect::new(p, p))
+            .reduce(|a, b| a.union_bbox(&b))
+    }
+
+    #[must_use]
+    pub fn min(&self) -> Point<T> {
+        self.min
+    }
+
+    #[must_use]
+    pub fn max(&self) -> Point<T> {
+        self.max
+    }
+
+    /// Returns `max.x - min.x`.
+    #[must_use]
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x
+    }
+
+    /// Returns `max.y - min.y`.
+    #[must_use]
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y
+    }
+
+    /// Returns the extent along each axis, `max - min`.
+    #[must_use]
+    pub fn size(&self) -> Point<T> {
+        self.max - self.min
+    }
+
+    /// Returns the number of grid tiles along each axis, both corner rows and columns included.
+    #[must_use]
+    pub fn inclusive_size(&self) -> Point<T> {
+        self.size() + Point::new(T::ONE, T::ONE)
+    }
+
+    /// Returns the area counted in grid tiles, both corner rows and columns included.
+    #[must_use]
+    pub fn inclusive_area(&self) -> T {
+        (self.width() + T::ONE) * (self.height() + T::ONE)
+    }
+
+    /// Returns the geometric area, `width * height`.
+    #[must_use]
+    pub fn exclusive_area(&self) -> T {
+        self.width() * self.height()
+    }
+
+    /// Returns `true` if the point is inside the rectangle or on its border.
+    #[must_use]
+    pub fn contains(&self, point: Point<T>) -> bool {
+        self.min.x <= point.x
+            && point.x <= self.max.x
+            && self.min.y <= point.y
+            && point.y <= self.max.y
+    }
+
+    /// Returns `true` if `other` lies entirely inside this rectangle.
+    #[must_use]
+    pub fn contains_rect(&self, other: &Self) -> bool {
+        self.contains(other.min) && self.contains(other.max)
+    }
+
+    /// Returns `true` if the rectangles share at least one point (touching borders count).
+    #[must_use]
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    /// Returns the overlap of the two rectangles, if any.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.intersects(other).then(|| Rect {
+            min: Point::new(
+                max_of(self.min.x, other.min.x),
+                max_of(self.min.y, other.min.y),
+            ),
+            max: Point::new(
+                min_of(self.max.x, other.max.x),
+                min_of(self.max.y, other.max.y),
+            ),
+        })
+    }
+
+    /// Returns the smallest rectangle containing both rectangles.
+    #[must_use]
+    pub fn union_bbox(&self, other: &Self) -> Self {
+        Rect {
+            min: Point::new(
+                min_of(self.min.x, other.min.x),
+                min_of(self.min.y, other.min.y),
+            ),
+            max: Point::new(
+                max_of(self.max.x, other.max.x),
+                max_of(self.max.y, other.max.y),
+            ),
+        }
+    }
+
+    /// Iterates over the integer cells of the rectangle, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = Point<T>> {
+        let xs = steps(self.min.x, self.max.x);
+
+        steps(self.min.y, self.max.y).flat_map(move |y| xs.clone().map(move |x| Point::new(x, y)))
+    }
+}
+
+impl<T: Number> From<(Point<T>, Point<T>)> for Rect<T> {
+    fn from((a, b): (Point<T>, Point<T>)) -> Self {
+        Rect::new(a, b)
+    }
+}
+
+impl<T: Number> fmt::Display for Rect<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {}]", self.min, self.max)
+    }
+}
+
+/// Axis-aligned box with both corners included, always stored as `(min, max)`.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Rect3D<T: Number> {
+    min: Point3D<T>,
+    max: Point3D<T>,
+}
+
+impl<T: Number> Rect3D<T> {
+    /// Creates the box with opposite corners `a` and `b`, in any order.
+    #[must_use]
+    pub fn new(a: Point3D<T>, b: Point3D<T>) -> Self {
+        Rect3D {
+            min: Point3D::new(min_of(a.x, b.x), min_of(a.y, b.y), min_of(a.z, b.z)),
+            max: Point3D::new(max_of(a.x, b.x), max_of(a.y, b.y), max_of(a.z, b.z)),
+        }
+    }
+
+    /// Returns the smallest box containing every point, or `None` if there are none.
+    pub fn bounding_box(points: impl IntoIterator<Item = Point3D<T>>) -> Option<Self> {
+        points
+            .into_iter()
+            .map(|p| Rect3D::new(p, p))
+            .reduce(|a, b| a.union_bbox(&b))
+    }
+
+    #[must_use]
+    pub fn min(&self) -> Point3D<T> {
+        self.min
+    }
+
+    #[must_use]
+    pub fn max(&self) -> Point3D<T> {
+        self.max
+    }
+
+    /// Returns the extent along each axis, `max - min`.
+    #[must_use]
+    pub fn size(&self) -> Point3D<T> {
+        self.max - self.min
+    }
+
+    /// Returns the volume counted in unit cubes, both corner layers included.
+    #[must_use]
+    pub fn inclusive_volume(&self) -> T {
+        let size = self.size();
+
+        (size.x + T::ONE) * (size.y + T::ONE) * (size.z + T::ONE)
+    }
+
+    /// Returns the geometric volume.
+    #[must_use]
+    pub fn exclusive_volume(&self) -> T {
+        let size = self.size();
+
+        size.x * size.y * size.z
+    }
+
+    /// Returns `true` if the point is inside the box or on its surface.
+    #[must_use]
+    pub fn contains(&self, point: Point3D<T>) -> bool {
+        self.min.x <= point.x
+            && point.x <= self.max.x
+            && self.min.y <= point.y
+            && point.y <= self.max.y
+            && self.min.z <= point.z
+            && point.z <= self.max.z
+    }
+
+    /// Returns `true` if the boxes share at least one point (touching faces count).
+    #[must_use]
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+            && self.min.z <= other.max.z
+            && other.min.z <= self.max.z
+    }
+
+    /// Returns the overlap of the two boxes, if any.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.intersects(other).then(|| Rect3D {
+            min: Point3D::new(
+                max_of(self.min.x, other.min.x),
+                max_of(self.min.y, other.min.y),
+                max_of(self.min.z, other.min.z),
+            ),
+            max: Point3D::new(
+                min_of(self.max.x, other.max.x),
+                min_of(self.max.y, other.max.y),
+                min_of(self.max.z, other.max.z),
+            ),
+        })
+    }
+
+    /// Returns the smallest box containing both boxes.
+    #[must_use]
+    pub fn union_bbox(&self, other: &Self) -> Self {
+        Rect3D {
+            min: Point3D::new(
+                min_of(self.min.x, other.min.x),
+                min_of(self.min.y, other.min.y),
+                min_of(self.min.z, other.min.z),
+            ),
+            max: Point3D::new(
+                max_of(self.max.x, other.max.x),
+                max_of(self.max.y, other.max.y),
+                max_of(self.max.z, other.max.z),
+            ),
+        }
+    }
+
+    /// Iterates over the integer cells of the box, layer by layer then row by row.
+    pub fn cells(&self) -> impl Iterator<Item = Point3D<T>> {
+        let xs = steps(self.min.x, self.max.x);
+        let ys = steps(self.min.y, self.max.y);
+
+        steps(self.min.z, self.max.z).flat_map(move |z| {
+            let xs = xs.clone();
+            ys.clone()
+                .flat_map(move |y| xs.clone().map(move |x| Point3D::new(x, y, z)))
+        })
+    }
+}
+
+impl<T: Number> From<(Point3D<T>, Point3D<T>)> for Rect3D<T> {
+    fn from((a, b): (Point3D<T>, Point3D<T>)) -> Self {
+        Rect3D::new(a, b)
+    }
+}
+
+impl<T: Number> fmt::Display for Rect3D<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {}]", self.min, self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rect_normalization() {
+        let rect = Rect::new(Point::new(9, 1), Point::new(2, 5));
+
+        assert_eq!(rect.min(), Point::new(2, 1));
+        assert_eq!(rect.max(), Point::new(9, 5));
+        assert_eq!(rect, Rect::from((Point::new(2, 5), Point::new(9, 1))));
+        assert_eq!(format!("{rect}"), "[(2, 1), (9, 5)]");
+    }
+
+    #[test]
+    fn test_rect_area() {
+        let rect = Rect::new(Point::new(2, 3), Point::new(9, 5));
+        assert_eq!(rect.inclusive_area(), 24);
+        assert_eq!(rect.exclusive_area(), 14);
+        assert_eq!(rect.size(), Point::new(7, 2));
+        assert_eq!(rect.inclusive_size(), Point::new(8, 3));
+
+        let line = Rect::new(Point::new(0u32, 4), Point::new(6, 4));
+        assert_eq!(line.inclusive_area(), 7);
+        assert_eq!(line.exclusive_area(), 0);
+    }
+
+    #[test]
+    fn test_rect_contains() {
+        let rect = Rect::new(Point::new(0, 0), Point::new(4, 3));
+
+        assert!(rect.contains(Point::new(0, 0)));
+        assert!(rect.contains(Point::new(4, 3)));
+        assert!(rect.contains(Point::new(2, 1)));
+        assert!(!rect.contains(Point::new(5, 1)));
+        assert!(!rect.contains(Point::new(2, -1)));
+
+        assert!(rect.contains_rect(&Rect::new(Point::new(1, 1), Point::new(4, 2))));
+        assert!(!rect.contains_rect(&Rect::new(Point::new(1, 1), Point::new(5, 2))));
+    }
+
+    #[test]
+    fn test_rect_intersection_and_union() {
+        let a = Rect::new(Point::new(0, 0), Point::new(4, 4));
+        let b = Rect::new(Point::new(2, 3), Point::new(6, 8));
+        let c = Rect::new(Point::new(5, 5), Point::new(7, 7));
+
+        assert!(a.intersects(&b));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Rect::new(Point::new(2, 3), Point::new(4, 4)))
+        );
+        assert!(!a.intersects(&c));
+        assert_eq!(a.intersection(&c), None);
+
+        // Touching borders share a line
+        let d = Rect::new(Point::new(4, 0), Point::new(5, 1));
+        assert_eq!(
+            a.intersection(&d),
+            Some(Rect::new(Point::new(4, 0), Point::new(4, 1)))
+        );
+
+        assert_eq!(
+            a.union_bbox(&c),
+            Rect::new(Point::new(0, 0), Point::new(7, 7))
+        );
+        assert_eq!(
+            Rect::bounding_box([Point::new(3, -1), Point::new(-2, 4), Point::new(0, 0)]),
+            Some(Rect::new(Point::new(-2, -1), Point::new(3, 4)))
+        );
+        assert_eq!(Rect::<i32>::bounding_box([]), None);
+    }
+
+    #[test]
+    fn test_rect_cells() {
+        let rect = Rect::new(Point::new(1usize, 2), Point::new(2, 3));
+        let cells: Vec<_> = rect.cells().collect();
+
+        assert_eq!(
+            cells,
+            vec![
+                Point::new(1, 2),
+                Point::new(2, 2),
+                Point::new(1, 3),
+                Point::new(2, 3)
+            ]
+        );
+        assert_eq!(
+            Rect::new(Point::new(0, 0), Point::new(0, 0))
+                .cells()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_rect3d() {
+        let a = Rect3D::new(Point3D::new(3, 0, 2), Point3D::new(0, 2, 0));
+        let b = Rect3D::new(Point3D::new(2, 2, 2), Point3D::new(5, 5, 5));
+
+        assert_eq!(a.min(), Point3D::new(0, 0, 0));
+        assert_eq!(a.max(), Point3D::new(3, 2, 2));
+        assert_eq!(a.inclusive_volume(), 36);
+        assert_eq!(a.exclusive_volume(), 12);
+        assert!(a.contains(Point3D::new(3, 1, 2)));
+        assert!(!a.contains(Point3D::new(3, 1, 3)));
+
+        assert_eq!(
+            a.intersection(&b),
+            Some(Rect3D::new(Point3D::new(2, 2, 2), Point3D::new(3, 2, 2)))
+        );
+        assert_eq!(
+            a.union_bbox(&b),
+            Rect3D::new(Point3D::new(0, 0, 0), Point3D::new(5, 5, 5))
+        );
+        assert_eq!(a.cells().count(), 36);
+        assert_eq!(b.cells().next(), Some(Point3D::new(2, 2, 2)));
+    }
+}
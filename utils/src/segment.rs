@@ -0,0 +1,479 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::geometry::Orientation;
+use crate::points::{Number, Point};
+use crate::rect::Rect;
+
+/// Integer coordinates that can be widened to `i128`, so that orientation predicates are exact.
+///
+/// Differences of two coordinates always fit in an `i128`; their products are compared with
+/// 256-bit arithmetic when they don't.
+pub trait Widen: Number + Ord {
+    fn widen(self) -> i128;
+}
+
+macro_rules! impl_widen {
+    ($($t:ty),*) => {
+        $(
+            impl Widen for $t {
+                fn widen(self) -> i128 {
+                    i128::from(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_widen!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+impl Widen for isize {
+    fn widen(self) -> i128 {
+        self as i128
+    }
+}
+
+impl Widen for usize {
+    fn widen(self) -> i128 {
+        self as i128
+    }
+}
+
+/// Line segment from `start` to `end`, both included.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Segment<T: Number> {
+    pub start: Point<T>,
+    pub end: Point<T>,
+}
+
+/// How two segments meet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentIntersection<T: Number> {
+    Disjoint,
+    /// The segments cross at a single point inside both of them, which may not be a lattice point
+    Proper,
+    /// The segments share a single point, an endpoint of at least one of them
+    Touching(Point<T>),
+    /// The segments are collinear and share this sub-segment
+    Overlap(Segment<T>),
+}
+
+impl<T: Number> Segment<T> {
+    #[must_use]
+    pub fn new(start: Point<T>, end: Point<T>) -> Self {
+        Segment { start, end }
+    }
+
+    #[must_use]
+    pub fn is_point(&self) -> bool {
+        self.start == self.end
+    }
+
+    #[must_use]
+    pub fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+
+    #[must_use]
+    pub fn is_vertical(&self) -> bool {
+        self.start.x == self.end.x
+    }
+
+    #[must_use]
+    pub fn bounding_box(&self) -> Rect<T> {
+        Rect::new(self.start, self.end)
+    }
+}
+
+impl<T: Widen> Segment<T> {
+    /// Returns `true` if the point lies on the segment, endpoints included.
+    #[must_use]
+    pub fn contains(&self, point: Point<T>) -> bool {
+        orientation(self.start, self.end, point) == Orientation::Degenerate
+            && self.bounding_box().contains(point)
+    }
+
+    /// Classifies how the two segments meet, using exact arithmetic.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> SegmentIntersection<T> {
+        let (a, b, c, d) = (self.start, self.end, other.start, other.end);
+        let o1 = orientation(a, b, c);
+        let o2 = orientation(a, b, d);
+        let o3 = orientation(c, d, a);
+        let o4 = orientation(c, d, b);
+
+        if [o1, o2, o3, o4]
+            .iter()
+            .all(|&o| o == Orientation::Degenerate)
+        {
+            return self.collinear_intersection(other);
+        }
+
+        if o1 == o2 || o3 == o4 {
+            return SegmentIntersection::Disjoint;
+        }
+
+        // Lines that separate each other's endpoints meet at a single point, which is an
+        // endpoint when it lies on the other line
+        [(o1, c), (o2, d), (o3, a), (o4, b)]
+            .into_iter()
+            .find(|&(o, _)| o == Orientation::Degenerate)
+            .map_or(SegmentIntersection::Proper, |(_, point)| {
+                SegmentIntersection::Touching(point)
+            })
+    }
+
+    /// Returns `true` if the segments share at least one point.
+    #[must_use]
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other) != SegmentIntersection::Disjoint
+    }
+
+    /// Returns `true` if the segment shares at least one point with the rectangle, border
+    /// included.
+    #[must_use]
+    pub fn intersects_rect(&self, rect: &Rect<T>) -> bool {
+        let (min, max) = (rect.min(), rect.max());
+        let corners = [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)];
+
+        rect.contains(self.start)
+            || rect.contains(self.end)
+            || (0..4).any(|i| self.intersects(&Segment::new(corners[i], corners[(i + 1) % 4])))
+    }
+
+    /// Returns `true` if the segment passes through the open interior of the rectangle, i.e.
+    /// it shares more with the rectangle than points of its border.
+    #[must_use]
+    pub fn crosses_interior(&self, rect: &Rect<T>) -> bool {
+        let (min, max) = (rect.min(), rect.max());
+        let (lo, hi) = (self.bounding_box().min(), self.bounding_box().max());
+
+        if self.is_vertical() {
+            return min.x < lo.x && lo.x < max.x && lo.y < max.y && hi.y > min.y;
+        }
+        if self.is_horizontal() {
+            return min.y < lo.y && lo.y < max.y && lo.x < max.x && hi.x > min.x;
+        }
+
+        // A diagonal can't run along the border, so it reaches the interior exactly when it
+        // shares at least two points with the rectangle
+        let corners = [min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)];
+        let mut shared: Vec<Point<T>> = [self.start, self.end]
+            .into_iter()
+            .filter(|&p| rect.contains(p))
+            .collect();
+        for i in 0..4 {
+            match self.intersection(&Segment::new(corners[i], corners[(i + 1) % 4])) {
+                SegmentIntersection::Proper => return true,
+                SegmentIntersection::Touching(point) => shared.push(point),
+                SegmentIntersection::Disjoint | SegmentIntersection::Overlap(_) => {}
+            }
+        }
+        shared.sort_unstable_by_key(|p| (p.x, p.y));
+        shared.dedup();
+
+        shared.len() > 1
+    }
+
+    /// Clips the segment to the rectangle (Liang–Barsky), returning the part inside it.
+    ///
+    /// Clipped endpoints of a diagonal segment are generally not lattice points, so the result
+    /// is in floating point.
+    #[must_use]
+    pub fn clip(&self, rect: &Rect<T>) -> Option<Segment<f64>> {
+        let to_f64 = |p: Point<T>| Point::new(p.x.widen() as f64, p.y.widen() as f64);
+        let (start, end) = (to_f64(self.start), to_f64(self.end));
+        let (min, max) = (to_f64(rect.min()), to_f64(rect.max()));
+        let delta = end - start;
+
+        let mut t0 = 0.0_f64;
+        let mut t1 = 1.0_f64;
+        for (p, q) in [
+            (-delta.x, start.x - min.x),
+            (delta.x, max.x - start.x),
+            (-delta.y, start.y - min.y),
+            (delta.y, max.y - start.y),
+        ] {
+            if p == 0.0 {
+                if q < 0.0 {
+                    return None;
+                }
+            } else if p < 0.0 {
+                t0 = t0.max(q / p);
+            } else {
+                t1 = t1.min(q / p);
+            }
+        }
+
+        let at = |t: f64| Point::new(start.x + t * delta.x, start.y + t * delta.y);
+
+        (t0 <= t1).then(|| Segment::new(at(t0), at(t1)))
+    }
+
+    /// Intersects two segments lying on the same line by projecting them onto an axis.
+    fn collinear_intersection(&self, other: &Self) -> SegmentIntersection<T> {
+        let vertical = [other.start, other.end, self.end]
+            .iter()
+            .all(|p| p.x == self.start.x);
+        let key = |p: &Point<T>| if vertical { p.y } else { p.x };
+        let sorted = |s: &Self| {
+            if key(&s.start) <= key(&s.end) {
+                (s.start, s.end)
+            } else {
+                (s.end, s.start)
+            }
+        };
+
+        let (lo1, hi1) = sorted(self);
+        let (lo2, hi2) = sorted(other);
+        let lo = if key(&lo1) >= key(&lo2) { lo1 } else { lo2 };
+        let hi = if key(&hi1) <= key(&hi2) { hi1 } else { hi2 };
+
+        match key(&lo).cmp(&key(&hi)) {
+            Ordering::Greater => SegmentIntersection::Disjoint,
+            Ordering::Equal => SegmentIntersection::Touching(lo),
+            Ordering::Less => SegmentIntersection::Overlap(Segment::new(lo, hi)),
+        }
+    }
+}
+
+impl<T: Number> From<(Point<T>, Point<T>)> for Segment<T> {
+    fn from((start, end): (Point<T>, Point<T>)) -> Self {
+        Segment::new(start, end)
+    }
+}
+
+impl<T: Number> fmt::Display for Segment<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.start, self.end)
+    }
+}
+
+/// Returns the orientation of the turn `a` -> `b` -> `c`, computed exactly.
+///
+/// `CounterClockwise` means `c` is to the left of the line from `a` to `b` (y axis pointing
+/// up), `Degenerate` that the three points are collinear.
+#[must_use]
+pub fn orientation<T: Widen>(a: Point<T>, b: Point<T>, c: Point<T>) -> Orientation {
+    let (ax, ay) = (a.x.widen(), a.y.widen());
+
+    match compare_products(
+        b.x.widen() - ax,
+        c.y.widen() - ay,
+        b.y.widen() - ay,
+        c.x.widen() - ax,
+    ) {
+        Ordering::Greater => Orientation::CounterClockwise,
+        Ordering::Less => Orientation::Clockwise,
+        Ordering::Equal => Orientation::Degenerate,
+    }
+}
+
+/// Compares `a * b` with `c * d` without overflowing.
+fn compare_products(a: i128, b: i128, c: i128, d: i128) -> Ordering {
+    if let (Some(left), Some(right)) = (a.checked_mul(b), c.checked_mul(d)) {
+        return left.cmp(&right);
+    }
+
+    let sign = |x: i128, y: i128| x.signum() * y.signum();
+    let (left_sign, right_sign) = (sign(a, b), sign(c, d));
+    if left_sign != right_sign {
+        return left_sign.cmp(&right_sign);
+    }
+
+    let magnitudes = wide_mul(a.unsigned_abs(), b.unsigned_abs())
+        .cmp(&wide_mul(c.unsigned_abs(), d.unsigned_abs()));
+
+    if left_sign < 0 {
+        magnitudes.reverse()
+    } else {
+        magnitudes
+    }
+}
+
+/// Multiplies two `u128` into a 256-bit `(high, low)` pair.
+fn wide_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let middle = (lo_lo >> 64) + (hi_lo & MASK) + (lo_hi & MASK);
+    let low = (middle << 64) | (lo_lo & MASK);
+    let high = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (middle >> 64);
+
+    (high, low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(a: (i64, i64), b: (i64, i64)) -> Segment<i64> {
+        Segment::new(a.into(), b.into())
+    }
+
+    #[test]
+    fn test_orientation() {
+        let (a, b) = (Point::new(0, 0), Point::new(4, 4));
+
+        assert_eq!(
+            orientation(a, b, Point::new(0, 4)),
+            Orientation::CounterClockwise
+        );
+        assert_eq!(orientation(a, b, Point::new(4, 0)), Orientation::Clockwise);
+        assert_eq!(orientation(a, b, Point::new(9, 9)), Orientation::Degenerate);
+    }
+
+    #[test]
+    fn test_orientation_is_exact_at_extremes() {
+        // Products of these differences overflow i128
+        let a = Point::new(i64::MIN, i64::MIN);
+        let b = Point::new(i64::MAX, i64::MAX - 1);
+        let c = Point::new(i64::MAX - 1, i64::MAX - 2);
+
+        // The cross product is exactly -1
+        assert_eq!(orientation(a, b, c), Orientation::Clockwise);
+        assert_eq!(orientation(b, a, c), Orientation::CounterClockwise);
+        assert_eq!(orientation(a, b, b), Orientation::Degenerate);
+
+        let (lo, hi) = (Point::new(0u64, 0), Point::new(u64::MAX, u64::MAX));
+        assert_eq!(
+            orientation(lo, hi, Point::new(u64::MAX - 1, u64::MAX - 1)),
+            Orientation::Degenerate
+        );
+    }
+
+    #[test]
+    fn test_wide_mul() {
+        assert_eq!(wide_mul(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+        assert_eq!(wide_mul(1 << 64, 1 << 64), (1, 0));
+        assert_eq!(wide_mul(12, 34), (0, 408));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let diagonal = segment((0, 0), (4, 4));
+
+        assert_eq!(
+            diagonal.intersection(&segment((0, 4), (4, 0))),
+            SegmentIntersection::Proper
+        );
+        assert_eq!(
+            diagonal.intersection(&segment((0, 1), (3, 4))),
+            SegmentIntersection::Disjoint
+        );
+        assert_eq!(
+            diagonal.intersection(&segment((2, 2), (5, 0))),
+            SegmentIntersection::Touching(Point::new(2, 2))
+        );
+        assert_eq!(
+            diagonal.intersection(&segment((4, 4), (6, 1))),
+            SegmentIntersection::Touching(Point::new(4, 4))
+        );
+
+        // Crossing at a non-lattice point is still proper
+        assert_eq!(
+            segment((0, 0), (1, 1)).intersection(&segment((0, 1), (1, 0))),
+            SegmentIntersection::Proper
+        );
+    }
+
+    #[test]
+    fn test_collinear_intersection() {
+        let diagonal = segment((0, 0), (4, 4));
+
+        assert_eq!(
+            diagonal.intersection(&segment((6, 6), (2, 2))),
+            SegmentIntersection::Overlap(segment((2, 2), (4, 4)))
+        );
+        assert_eq!(
+            diagonal.intersection(&segment((4, 4), (7, 7))),
+            SegmentIntersection::Touching(Point::new(4, 4))
+        );
+        assert_eq!(
+            diagonal.intersection(&segment((5, 5), (7, 7))),
+            SegmentIntersection::Disjoint
+        );
+
+        let vertical = segment((3, 0), (3, 5));
+        assert_eq!(
+            vertical.intersection(&segment((3, 4), (3, 9))),
+            SegmentIntersection::Overlap(segment((3, 4), (3, 5)))
+        );
+
+        // Point segments
+        assert_eq!(
+            vertical.intersection(&segment((3, 2), (3, 2))),
+            SegmentIntersection::Touching(Point::new(3, 2))
+        );
+        assert_eq!(
+            segment((0, 0), (0, 0)).intersection(&segment((5, 0), (5, 0))),
+            SegmentIntersection::Disjoint
+        );
+    }
+
+    #[test]
+    fn test_contains() {
+        let s = segment((1, 1), (7, 4));
+
+        assert!(s.contains(Point::new(3, 2)));
+        assert!(s.contains(Point::new(7, 4)));
+        assert!(!s.contains(Point::new(9, 5)));
+        assert!(!s.contains(Point::new(3, 3)));
+    }
+
+    #[test]
+    fn test_rect() {
+        let rect = Rect::new(Point::new(0, 0), Point::new(4, 4));
+
+        let crossing = segment((-2, 3), (3, -2));
+        assert!(crossing.intersects_rect(&rect));
+        assert_eq!(
+            crossing.clip(&rect),
+            Some(Segment::new(Point::new(0.0, 1.0), Point::new(1.0, 0.0)))
+        );
+
+        let inside = segment((1, 1), (2, 3));
+        assert!(inside.intersects_rect(&rect));
+        assert_eq!(
+            inside.clip(&rect),
+            Some(Segment::new(Point::new(1.0, 1.0), Point::new(2.0, 3.0)))
+        );
+
+        let outside = segment((3, 7), (7, 3));
+        assert!(!outside.intersects_rect(&rect));
+        assert_eq!(outside.clip(&rect), None);
+
+        // Passing through a corner only
+        let corner = segment((2, 6), (6, 2));
+        assert!(corner.intersects_rect(&rect));
+        assert_eq!(
+            corner.clip(&rect),
+            Some(Segment::new(Point::new(4.0, 4.0), Point::new(4.0, 4.0)))
+        );
+    }
+
+    #[test]
+    fn test_crosses_interior() {
+        let rect = Rect::new(Point::new(0, 0), Point::new(4, 4));
+
+        assert!(segment((-2, 3), (3, -2)).crosses_interior(&rect));
+        assert!(segment((1, 1), (2, 3)).crosses_interior(&rect));
+        // Corner to corner, and from a border point inwards
+        assert!(segment((-1, -1), (5, 5)).crosses_interior(&rect));
+        assert!(segment((0, 2), (1, 3)).crosses_interior(&rect));
+        assert!(segment((2, -1), (2, 5)).crosses_interior(&rect));
+
+        // Only touching the border
+        assert!(!segment((2, 6), (6, 2)).crosses_interior(&rect));
+        assert!(!segment((0, 2), (-1, 3)).crosses_interior(&rect));
+        assert!(!segment((0, -1), (0, 5)).crosses_interior(&rect));
+        assert!(!segment((3, 7), (7, 3)).crosses_interior(&rect));
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::point3d::Point3D;
+use crate::rect::Rect3D;
+
+/// Which voxels count as adjacent when grouping them into components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Voxels sharing a face
+    Six,
+    /// Voxels sharing a face, an edge or a corner
+    TwentySix,
+}
+
+/// Offsets from a voxel to the 26 around it, the 6 sharing a face first.
+const OFFSETS: [[i32; 3]; 26] = offsets();
+
+const fn offsets() -> [[i32; 3]; 26] {
+    let mut offsets = [[0; 3]; 26];
+    let mut count = 0;
+
+    // Offsets along one axis share a face, along two an edge and along three a corner
+    let mut axes = 1;
+    while axes <= 3 {
+        let mut i = 0;
+        while i < 27 {
+            let offset = [i / 9 - 1, i / 3 % 3 - 1, i % 3 - 1];
+            let moved = (offset[0] != 0) as i32 + (offset[1] != 0) as i32 + (offset[2] != 0) as i32;
+            if moved == axes {
+                offsets[count] = offset;
+                count += 1;
+            }
+            i += 1;
+        }
+        axes += 1;
+    }
+
+    offsets
+}
+
+impl Connectivity {
+    /// Returns the neighbors of a voxel, `None` for those past the edge of the `i32` lattice.
+    fn neighbors(self, voxel: Point3D<i32>) -> impl Iterator<Item = Option<Point3D<i32>>> {
+        let offsets = match self {
+            Connectivity::Six => &OFFSETS[..6],
+            Connectivity::TwentySix => &OFFSETS[..],
+        };
+
+        offsets.iter().map(move |&[dx, dy, dz]| {
+            Some(Point3D::new(
+                voxel.x.checked_add(dx)?,
+                voxel.y.checked_add(dy)?,
+                voxel.z.checked_add(dz)?,
+            ))
+        })
+    }
+}
+
+/// Set of unit cubes on the integer lattice.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VoxelSet {
+    voxels: HashSet<Point3D<i32>>,
+}
+
+impl VoxelSet {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a voxel, returning `false` if it was already there.
+    pub fn insert(&mut self, voxel: Point3D<i32>) -> bool {
+        self.voxels.insert(voxel)
+    }
+
+    /// Removes a voxel, returning `false` if it wasn't there.
+    pub fn remove(&mut self, voxel: &Point3D<i32>) -> bool {
+        self.voxels.remove(voxel)
+    }
+
+    #[must_use]
+    pub fn contains(&self, voxel: &Point3D<i32>) -> bool {
+        self.voxels.contains(voxel)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.voxels.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.voxels.is_empty()
+    }
+
+    /// Iterates over the voxels, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Point3D<i32>> {
+        self.voxels.iter()
+    }
+
+    /// Returns the smallest box containing every voxel, or `None` if the set is empty.
+    #[must_use]
+    pub fn bounding_box(&self) -> Option<Rect3D<i32>> {
+        Rect3D::bounding_box(self.voxels.iter().copied())
+    }
+
+    /// Counts the voxel faces that don't touch another voxel, internal cavities included.
+    #[must_use]
+    pub fn surface_area(&self) -> usize {
+        self.voxels
+            .iter()
+            .flat_map(|&voxel| Connectivity::Six.neighbors(voxel))
+            .filter(|neighbor| neighbor.is_none_or(|neighbor| !self.contains(&neighbor)))
+            .count()
+    }
+
+    /// Counts the voxel faces reachable from outside, leaving out those of internal cavities.
+    ///
+    /// Flood fills the air in the bounding box grown by one in every direction, counting the
+    /// faces the fill runs into. Faces on the edge of the `i32` lattice, where the box can't
+    /// grow, count as outside.
+    #[must_use]
+    pub fn exterior_surface_area(&self) -> usize {
+        let Some(bounds) = self.bounding_box() else {
+            return 0;
+        };
+        let (min, max) = (bounds.min(), bounds.max());
+        let space = Rect3D::new(
+            Point3D::new(
+                min.x.saturating_sub(1),
+                min.y.saturating_sub(1),
+                min.z.saturating_sub(1),
+            ),
+            Point3D::new(
+                max.x.saturating_add(1),
+                max.y.saturating_add(1),
+                max.z.saturating_add(1),
+            ),
+        );
+
+        // Any air corner of the box is outside, and one of them is unless the box spans the
+        // whole lattice
+        let (low, high) = (space.min(), space.max());
+        let mut seen: HashSet<Point3D<i32>> = [low.x, high.x]
+            .into_iter()
+            .flat_map(|x| [low.y, high.y].map(|y| (x, y)))
+            .flat_map(|(x, y)| [low.z, high.z].map(|z| Point3D::new(x, y, z)))
+            .filter(|corner| !self.contains(corner))
+            .collect();
+        let mut queue: VecDeque<Point3D<i32>> = seen.iter().copied().collect();
+        let mut faces = self
+            .voxels
+            .iter()
+            .flat_map(|&voxel| Connectivity::Six.neighbors(voxel))
+            .filter(Option::is_none)
+            .count();
+
+        while let Some(air) = queue.pop_front() {
+            for neighbor in Connectivity::Six.neighbors(air).flatten() {
+                if self.contains(&neighbor) {
+                    faces += 1;
+                } else if space.contains(neighbor) && seen.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        faces
+    }
+
+    /// Splits the set into its connected components.
+    ///
+    /// Components come in no particular order.
+    #[must_use]
+    pub fn components(&self, connectivity: Connectivity) -> Vec<VoxelSet> {
+        let mut remaining = self.voxels.clone();
+        let mut components = Vec::new();
+
+        // Voxels of components already found are skipped, so the set is walked only once
+        for &start in &self.voxels {
+            if !remaining.remove(&start) {
+                continue;
+            }
+
+            let mut component = VoxelSet::new();
+            let mut stack = vec![start];
+            while let Some(voxel) = stack.pop() {
+                component.insert(voxel);
+
+                for neighbor in connectivity.neighbors(voxel).flatten() {
+                    if remaining.remove(&neighbor) {
+                        stack.push(neighbor);
+                    }
+                }
+            }
+
+            components.push(component);
+        }
+
+        components
+    }
+}
+
+impl FromIterator<Point3D<i32>> for VoxelSet {
+    fn from_iter<I: IntoIterator<Item = Point3D<i32>>>(iter: I) -> Self {
+        VoxelSet {
+            voxels: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Point3D<i32>> for VoxelSet {
+    fn extend<I: IntoIterator<Item = Point3D<i32>>>(&mut self, iter: I) {
+        self.voxels.extend(iter);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The example droplet from 2022 day 18
+    fn droplet() -> VoxelSet {
+        [
+            (2, 2, 2),
+            (1, 2, 2),
+            (3, 2, 2),
+            (2, 1, 2),
+            (2, 3, 2),
+            (2, 2, 1),
+            (2, 2, 3),
+            (2, 2, 4),
+            (2, 2, 6),
+            (1, 2, 5),
+            (3, 2, 5),
+            (2, 1, 5),
+            (2, 3, 5),
+        ]
+        .into_iter()
+        .map(Point3D::from)
+        .collect()
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let voxel = Point3D::new(5, -2, 0);
+
+        let faces: HashSet<_> = Connectivity::Six.neighbors(voxel).flatten().collect();
+        assert_eq!(faces, voxel.neighbors6().into_iter().collect());
+
+        let all: HashSet<_> = Connectivity::TwentySix.neighbors(voxel).flatten().collect();
+        assert_eq!(all, voxel.neighbors26().into_iter().collect());
+    }
+
+    #[test]
+    fn test_surface_area() {
+        let pair: VoxelSet = [Point3D::new(1, 1, 1), Point3D::new(2, 1, 1)]
+            .into_iter()
+            .collect();
+        assert_eq!(pair.surface_area(), 10);
+        assert_eq!(pair.exterior_surface_area(), 10);
+
+        let droplet = droplet();
+        assert_eq!(droplet.surface_area(), 64);
+        assert_eq!(droplet.exterior_surface_area(), 58);
+
+        assert_eq!(VoxelSet::new().exterior_surface_area(), 0);
+    }
+
+    #[test]
+    fn test_hollow_cube() {
+        let cube = Rect3D::new(Point3D::new(0, 0, 0), Point3D::new(2, 2, 2));
+        let mut shell: VoxelSet = cube.cells().collect();
+        shell.remove(&Point3D::new(1, 1, 1));
+
+        assert_eq!(shell.exterior_surface_area(), 54);
+        assert_eq!(shell.surface_area(), 54 + 6);
+    }
+
+    #[test]
+    fn test_components() {
+        // Two voxels touching by a corner, and one far away
+        let set: VoxelSet = [
+            Point3D::new(0, 0, 0),
+            Point3D::new(1, 1, 1),
+            Point3D::new(5, 5, 5),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(set.components(Connectivity::Six).len(), 3);
+
+        let mut sizes: Vec<usize> = set
+            .components(Connectivity::TwentySix)
+            .iter()
+            .map(VoxelSet::len)
+            .collect();
+        sizes.sort_unstable();
+        assert_eq!(sizes, vec![1, 2]);
+
+        // The air pocket at (2, 2, 5) is ringed by voxels that only touch by their edges
+        assert_eq!(droplet().components(Connectivity::Six).len(), 6);
+        assert_eq!(droplet().components(Connectivity::TwentySix).len(), 1);
+    }
+
+    #[test]
+    fn test_lattice_edge() {
+        let corner = Point3D::new(i32::MAX, i32::MAX, i32::MAX);
+        let set: VoxelSet = [corner, Point3D::new(i32::MAX - 1, i32::MAX, i32::MAX)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(set.surface_area(), 10);
+        assert_eq!(set.exterior_surface_area(), 10);
+        assert_eq!(set.components(Connectivity::TwentySix).len(), 1);
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        assert_eq!(
+            droplet().bounding_box(),
+            Some(Rect3D::new(Point3D::new(1, 1, 1), Point3D::new(3, 3, 6)))
+        );
+        assert_eq!(VoxelSet::new().bounding_box(), None);
+    }
+}